@@ -1,15 +1,36 @@
-use clap::{Parser, Subcommand};
+use clap::{Args as ClapArgs, Parser, Subcommand};
+use prometheus_mcp::mcp::alertmanager_client::{set_alertmanager, AlertmanagerClient};
+use prometheus_mcp::mcp::alertmanager_config::AlertmanagerConfig;
 use prometheus_mcp::mcp::compat;
 use prometheus_mcp::mcp::exporter;
 use prometheus_mcp::mcp::metrics;
+use prometheus_mcp::mcp::scrape::{set_scrape_allowed_hosts, set_scrape_timeout};
+use prometheus_mcp::mcp::tenant::{set_allowed_tenants, take_tenant_argument, with_tenant};
 use prometheus_mcp::mcp::tools::{
-    prometheus_get_label_values, prometheus_get_metadata, prometheus_get_series,
-    prometheus_list_metrics, prometheus_query, prometheus_query_range, register_tools,
-    PrometheusGetLabelValuesRequest, PrometheusGetMetadataRequest, PrometheusGetSeriesRequest,
-    PrometheusListMetricsRequest, PrometheusQueryRangeRequest, PrometheusQueryRequest,
+    alertmanager_create_silence, alertmanager_expire_silence, alertmanager_extend_silence,
+    alertmanager_get_status, alertmanager_list_alert_groups, alertmanager_list_alerts,
+    alertmanager_list_silences, prometheus_admin_clean_tombstones, prometheus_admin_delete_series,
+    prometheus_admin_snapshot, prometheus_federate, prometheus_format_query, prometheus_get_alerts,
+    prometheus_get_label_values, prometheus_get_metadata, prometheus_get_rules,
+    prometheus_get_series, prometheus_get_status, prometheus_get_targets_metadata,
+    prometheus_list_labels, prometheus_list_metrics, prometheus_parse_query, prometheus_query,
+    prometheus_query_exemplars, prometheus_query_range, prometheus_read_raw,
+    prometheus_scrape_target, prometheus_tsdb_status, register_tools, set_admin_tools_enabled,
+    set_backend_flavor, set_silence_tools_enabled, AlertmanagerAlertFilterRequest,
+    AlertmanagerCreateSilenceRequest, AlertmanagerExpireSilenceRequest,
+    AlertmanagerExtendSilenceRequest, AlertmanagerGetStatusRequest,
+    AlertmanagerListSilencesRequest, PrometheusAdminCleanTombstonesRequest,
+    PrometheusAdminDeleteSeriesRequest, PrometheusAdminSnapshotRequest, PrometheusFederateRequest,
+    PrometheusFormatQueryRequest, PrometheusGetAlertsRequest, PrometheusGetLabelValuesRequest,
+    PrometheusGetMetadataRequest, PrometheusGetRulesRequest, PrometheusGetSeriesRequest,
+    PrometheusGetStatusRequest, PrometheusGetTargetsMetadataRequest, PrometheusListLabelsRequest,
+    PrometheusListMetricsRequest, PrometheusParseQueryRequest, PrometheusQueryExemplarsRequest,
+    PrometheusQueryRangeRequest, PrometheusQueryRequest, PrometheusReadRawRequest,
+    PrometheusScrapeTargetRequest, PrometheusTsdbStatusRequest,
 };
 use prometheus_mcp::mcp::types::{
-    CancelledNotification, JsonRpcError, JsonRpcResponse, ToolCallRequestParams,
+    CallToolResult, CallToolResultContent, CancelledNotification, JsonRpcError, JsonRpcResponse,
+    ToolCallRequestParams,
 };
 use prometheus_mcp::mcp::utilities::*;
 use rpc_router::{Error, Handler, Request, Router, RouterBuilder};
@@ -17,9 +38,13 @@ use serde_json::{json, Value};
 use std::fs::OpenOptions;
 use std::io;
 use std::io::Write;
-use prometheus_mcp::mcp::prometheus_config::PrometheusConfig;
-use prometheus_mcp::mcp::repository::{set_repository, HttpPrometheusRepository};
+use prometheus_mcp::mcp::prometheus_config::{BackendFlavor, HttpHeader, PrometheusConfig};
+use prometheus_mcp::mcp::remote_read::RemoteReadRepository;
+use prometheus_mcp::mcp::repository::{
+    set_repository, HttpPrometheusRepository, PrometheusRepository,
+};
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Build the JSON-RPC router with prompts, resources, and tool handlers.
 fn build_rpc_router() -> Router {
@@ -36,6 +61,23 @@ fn build_rpc_router() -> Router {
     builder.build()
 }
 
+/// Thanos query parameters (require --flavor thanos)
+#[derive(ClapArgs, Debug)]
+struct ThanosQueryArgs {
+    /// Deduplicate series from HA replicas
+    #[arg(long)]
+    dedup: Option<bool>,
+    /// Return partial results with warnings when some stores fail
+    #[arg(long)]
+    partial_response: Option<bool>,
+    /// Coarsest downsampling resolution to use: 0s, 5m, 1h or auto
+    #[arg(long)]
+    max_source_resolution: Option<String>,
+    /// PromQL engine: prometheus or thanos
+    #[arg(long)]
+    engine: Option<String>,
+}
+
 #[derive(Subcommand, Debug)]
 /// CLI subcommands for interacting with Prometheus directly.
 enum PromCmd {
@@ -45,6 +87,17 @@ enum PromCmd {
         query: String,
         #[arg(long)]
         time: Option<String>,
+        /// Include query statistics (samples, timings)
+        #[arg(long, default_value = "false")]
+        stats: bool,
+        /// Server-side evaluation timeout (e.g. 10s)
+        #[arg(long)]
+        timeout: Option<String>,
+        /// Maximum number of returned series
+        #[arg(long)]
+        limit: Option<u64>,
+        #[command(flatten)]
+        thanos: ThanosQueryArgs,
     },
     /// Range query
     Range {
@@ -56,23 +109,225 @@ enum PromCmd {
         end: String,
         #[arg(long)]
         step: String,
+        /// Include query statistics (samples, timings)
+        #[arg(long, default_value = "false")]
+        stats: bool,
+        /// Server-side evaluation timeout (e.g. 10s)
+        #[arg(long)]
+        timeout: Option<String>,
+        /// Maximum number of returned series
+        #[arg(long)]
+        limit: Option<u64>,
+        #[command(flatten)]
+        thanos: ThanosQueryArgs,
     },
     /// List metric names
     ListMetrics,
-    /// Get metric metadata
+    /// Get metric metadata (all metrics when --metric is omitted)
     Metadata {
         #[arg(long)]
-        metric: String,
+        metric: Option<String>,
+        #[arg(long)]
+        limit: Option<u64>,
+        #[arg(long)]
+        limit_per_metric: Option<u64>,
+    },
+    /// Get metric metadata per scrape target
+    TargetsMetadata {
+        #[arg(long)]
+        match_target: Option<String>,
+        #[arg(long)]
+        metric: Option<String>,
+        #[arg(long)]
+        limit: Option<u64>,
     },
     /// Get a series for selectors (repeat --selector)
     Series {
         #[arg(long = "selector")]
         selectors: Vec<String>,
+        #[arg(long)]
+        start: Option<String>,
+        #[arg(long)]
+        end: Option<String>,
+        #[arg(long)]
+        limit: Option<u64>,
     },
-    /// Get label values
+    /// Get label values (optionally repeat --selector)
     LabelValues {
         #[arg(long = "label")]
         label_name: String,
+        #[arg(long = "selector")]
+        selectors: Vec<String>,
+        #[arg(long)]
+        start: Option<String>,
+        #[arg(long)]
+        end: Option<String>,
+        #[arg(long)]
+        limit: Option<u64>,
+    },
+    /// List label names (optionally repeat --selector)
+    ListLabels {
+        #[arg(long = "selector")]
+        selectors: Vec<String>,
+        #[arg(long)]
+        start: Option<String>,
+        #[arg(long)]
+        end: Option<String>,
+        #[arg(long)]
+        limit: Option<u64>,
+    },
+    /// Get active alerts (repeat --matcher)
+    Alerts {
+        #[arg(long)]
+        state: Option<String>,
+        #[arg(long)]
+        alertname: Option<String>,
+        #[arg(long = "matcher")]
+        matchers: Vec<String>,
+    },
+    /// Get alerting and recording rules
+    Rules {
+        /// alert or record
+        #[arg(long = "type")]
+        rule_type: Option<String>,
+        #[arg(long = "rule-name")]
+        rule_names: Vec<String>,
+        #[arg(long = "rule-group")]
+        rule_groups: Vec<String>,
+        #[arg(long = "file")]
+        files: Vec<String>,
+    },
+    /// Get server status (buildinfo, runtimeinfo, flags or config)
+    Status {
+        #[arg(long, default_value = "buildinfo")]
+        section: String,
+    },
+    /// Get TSDB cardinality statistics
+    TsdbStatus {
+        #[arg(long)]
+        limit: Option<u64>,
+    },
+    /// Query exemplars
+    Exemplars {
+        #[arg(long)]
+        query: String,
+        #[arg(long)]
+        start: Option<String>,
+        #[arg(long)]
+        end: Option<String>,
+    },
+    /// Format a PromQL expression using the server's formatter
+    FormatQuery {
+        #[arg(long)]
+        query: String,
+    },
+    /// Parse a PromQL expression into its syntax tree
+    ParseQuery {
+        #[arg(long)]
+        query: String,
+    },
+    /// Read raw samples for a selector (uses remote read when --remote-read-url is set)
+    ReadRaw {
+        #[arg(long)]
+        selector: String,
+        #[arg(long)]
+        start: String,
+        #[arg(long)]
+        end: String,
+    },
+    /// Read the latest samples of the matching series from /federate
+    Federate {
+        #[arg(long = "selector")]
+        selectors: Vec<String>,
+    },
+    /// Fetch and summarize a target's /metrics endpoint
+    ScrapeTarget {
+        #[arg(long)]
+        url: String,
+        #[arg(long)]
+        metric: Option<String>,
+        #[arg(long, default_value = "false")]
+        include_samples: bool,
+    },
+    /// List Alertmanager alerts with receivers and silence/inhibition status
+    AmAlerts {
+        #[arg(long = "filter")]
+        filters: Vec<String>,
+        #[arg(long)]
+        receiver: Option<String>,
+    },
+    /// List Alertmanager alert groups
+    AmGroups {
+        #[arg(long = "filter")]
+        filters: Vec<String>,
+        #[arg(long)]
+        receiver: Option<String>,
+    },
+    /// Show Alertmanager status and cluster peers
+    AmStatus,
+    /// List Alertmanager silences
+    AmSilences {
+        #[arg(long = "filter")]
+        filters: Vec<String>,
+        /// active, pending or expired
+        #[arg(long)]
+        state: Option<String>,
+    },
+    /// Create a silence (requires --enable-silence-tools; preview unless --confirm)
+    AmSilenceCreate {
+        #[arg(long = "matcher")]
+        matchers: Vec<String>,
+        /// e.g. 30m, 2h
+        #[arg(long)]
+        duration: String,
+        #[arg(long)]
+        comment: String,
+        #[arg(long)]
+        created_by: Option<String>,
+        /// Confirm token printed by the preview; applies the change
+        #[arg(long)]
+        confirm: Option<String>,
+    },
+    /// Extend a silence (requires --enable-silence-tools; preview unless --confirm)
+    AmSilenceExtend {
+        #[arg(long)]
+        id: String,
+        #[arg(long)]
+        duration: String,
+        /// Confirm token printed by the preview; applies the change
+        #[arg(long)]
+        confirm: Option<String>,
+    },
+    /// Expire a silence (requires --enable-silence-tools; preview unless --confirm)
+    AmSilenceExpire {
+        #[arg(long)]
+        id: String,
+        /// Confirm token printed by the preview; applies the change
+        #[arg(long)]
+        confirm: Option<String>,
+    },
+    /// Create a TSDB snapshot (requires --enable-admin-tools; dry run unless --execute)
+    AdminSnapshot {
+        #[arg(long, default_value = "false")]
+        skip_head: bool,
+        #[arg(long, default_value = "false")]
+        execute: bool,
+    },
+    /// Delete series matching selectors (requires --enable-admin-tools; dry run unless --execute)
+    AdminDeleteSeries {
+        #[arg(long = "selector")]
+        selectors: Vec<String>,
+        #[arg(long)]
+        start: Option<String>,
+        #[arg(long)]
+        end: Option<String>,
+        #[arg(long, default_value = "false")]
+        execute: bool,
+    },
+    /// Clean up tombstones (requires --enable-admin-tools; dry run unless --execute)
+    AdminCleanTombstones {
+        #[arg(long, default_value = "false")]
+        execute: bool,
     },
 }
 
@@ -91,6 +346,82 @@ struct Args {
     /// Basic auth password (or set PROMETHEUS_PASSWORD)
     #[arg(long, env = "PROMETHEUS_PASSWORD")]
     prometheus_password: Option<String>,
+    /// Bearer token (or set PROMETHEUS_BEARER_TOKEN)
+    #[arg(long, env = "PROMETHEUS_BEARER_TOKEN")]
+    prometheus_bearer_token: Option<String>,
+    /// File to read the bearer token from, re-read when it changes (e.g. a service account token)
+    #[arg(long, env = "PROMETHEUS_BEARER_TOKEN_FILE")]
+    prometheus_bearer_token_file: Option<String>,
+    /// PEM CA bundle to verify the Prometheus server with, instead of the system roots
+    #[arg(long, env = "PROMETHEUS_TLS_CA_FILE")]
+    tls_ca_file: Option<String>,
+    /// PEM client certificate for mutual TLS
+    #[arg(long, env = "PROMETHEUS_TLS_CERT_FILE")]
+    tls_cert_file: Option<String>,
+    /// PEM private key of the client certificate (PKCS#8, PKCS#1 or SEC1)
+    #[arg(long, env = "PROMETHEUS_TLS_KEY_FILE")]
+    tls_key_file: Option<String>,
+    /// Name to verify the server certificate against instead of the URL host
+    #[arg(long, env = "PROMETHEUS_TLS_SERVER_NAME")]
+    tls_server_name: Option<String>,
+    /// Skip server certificate verification (insecure)
+    #[arg(long, default_value = "false")]
+    tls_insecure_skip_verify: bool,
+    /// Header sent on every Prometheus request: Name=value, Name=env:VAR or Name=file:/path
+    /// (repeatable; PROMETHEUS_HEADERS takes one per line)
+    #[arg(long = "header", value_name = "NAME=VALUE")]
+    headers: Vec<HttpHeader>,
+    /// Proxy for http:// Prometheus URLs
+    #[arg(long, env = "PROMETHEUS_HTTP_PROXY")]
+    http_proxy: Option<String>,
+    /// Proxy for https:// Prometheus URLs
+    #[arg(long, env = "PROMETHEUS_HTTPS_PROXY")]
+    https_proxy: Option<String>,
+    /// Comma-separated hosts, domains and CIDRs reached without the proxy
+    #[arg(long, env = "PROMETHEUS_NO_PROXY", value_delimiter = ',')]
+    no_proxy: Vec<String>,
+    /// Switch query, series and label requests to POST above this many bytes of encoded parameters
+    #[arg(long, env = "PROMETHEUS_POST_THRESHOLD_BYTES")]
+    post_threshold_bytes: Option<usize>,
+    /// Always send query, series and label requests as POST
+    #[arg(long, default_value = "false")]
+    always_post: bool,
+    /// Register the TSDB admin tools (snapshot, delete series, clean tombstones)
+    #[arg(long, default_value = "false")]
+    enable_admin_tools: bool,
+    /// Remote-read endpoint for raw sample reads (e.g. http://prometheus:9090/api/v1/read)
+    #[arg(long, env = "PROMETHEUS_REMOTE_READ_URL")]
+    remote_read_url: Option<String>,
+    /// Tenant sent in the tenant header on every request (a|b queries several tenants)
+    #[arg(long, env = "PROMETHEUS_TENANT_ID")]
+    tenant_id: Option<String>,
+    /// Header carrying the tenant (default X-Scope-OrgID)
+    #[arg(long, env = "PROMETHEUS_TENANT_HEADER")]
+    tenant_header: Option<String>,
+    /// Comma-separated tenants a tool call may select with its `tenant` argument
+    #[arg(long, env = "PROMETHEUS_ALLOWED_TENANTS", value_delimiter = ',')]
+    allowed_tenants: Vec<String>,
+    /// Backend behind the URL: prometheus or thanos (thanos adds dedup, partial_response, ...)
+    #[arg(long, env = "PROMETHEUS_FLAVOR")]
+    flavor: Option<BackendFlavor>,
+    /// Comma-separated hosts prometheus_scrape_target may fetch from (host, host:port, .domain or *)
+    #[arg(long, env = "PROMETHEUS_SCRAPE_ALLOWED_HOSTS", value_delimiter = ',')]
+    scrape_allowed_hosts: Vec<String>,
+    /// Alertmanager URL; enables the Alertmanager tools
+    #[arg(long, env = "ALERTMANAGER_URL")]
+    alertmanager_url: Option<String>,
+    /// Alertmanager request timeout in seconds (default 10)
+    #[arg(long, env = "ALERTMANAGER_TIMEOUT")]
+    alertmanager_timeout: Option<u64>,
+    /// Alertmanager basic auth username (or set ALERTMANAGER_USERNAME)
+    #[arg(long, env = "ALERTMANAGER_USERNAME")]
+    alertmanager_username: Option<String>,
+    /// Alertmanager basic auth password (or set ALERTMANAGER_PASSWORD)
+    #[arg(long, env = "ALERTMANAGER_PASSWORD")]
+    alertmanager_password: Option<String>,
+    /// Register the tools that create, extend and expire Alertmanager silences
+    #[arg(long, default_value = "false")]
+    enable_silence_tools: bool,
     /// Enable Prometheus metrics exporter (HTTP /metrics)
     #[arg(long, default_value = "false")]
     metrics_exporter: bool,
@@ -107,7 +438,13 @@ async fn main() {
     let args = Args::parse();
 
     // Start from environment configuration, then apply CLI overrides if provided
-    let mut cfg = PrometheusConfig::from_env();
+    let mut cfg = match PrometheusConfig::from_env() {
+        Ok(cfg) => cfg,
+        Err(e) => {
+            eprintln!("Invalid Prometheus configuration: {}", e);
+            return;
+        }
+    };
     if let Some(url) = args.prometheus_url.clone() {
         cfg.url = url;
     }
@@ -117,13 +454,137 @@ async fn main() {
     if let Some(pass) = args.prometheus_password.clone() {
         cfg.password = Some(pass);
     }
+    if let Some(token) = args.prometheus_bearer_token.clone() {
+        cfg.bearer_token = Some(token);
+    }
+    if let Some(path) = args.prometheus_bearer_token_file.clone() {
+        cfg.bearer_token_file = Some(path);
+    }
+    if let Some(path) = args.tls_ca_file.clone() {
+        cfg.tls.ca_file = Some(path);
+    }
+    if let Some(path) = args.tls_cert_file.clone() {
+        cfg.tls.cert_file = Some(path);
+    }
+    if let Some(path) = args.tls_key_file.clone() {
+        cfg.tls.key_file = Some(path);
+    }
+    if let Some(name) = args.tls_server_name.clone() {
+        cfg.tls.server_name = Some(name);
+    }
+    if args.tls_insecure_skip_verify {
+        cfg.tls.insecure_skip_verify = true;
+    }
+    if !args.headers.is_empty() {
+        cfg.headers = args.headers.clone();
+    }
+    if let Some(proxy) = args.http_proxy.clone() {
+        cfg.http_proxy = Some(proxy);
+    }
+    if let Some(proxy) = args.https_proxy.clone() {
+        cfg.https_proxy = Some(proxy);
+    }
+    if !args.no_proxy.is_empty() {
+        cfg.no_proxy = args
+            .no_proxy
+            .iter()
+            .map(|h| h.trim().to_string())
+            .filter(|h| !h.is_empty())
+            .collect();
+    }
+    if let Some(bytes) = args.post_threshold_bytes {
+        cfg.post_threshold_bytes = bytes;
+    }
+    if args.always_post {
+        cfg.always_post = true;
+    }
+    if args.enable_admin_tools {
+        cfg.enable_admin_tools = true;
+    }
+    if let Some(url) = args.remote_read_url.clone() {
+        cfg.remote_read_url = Some(url);
+    }
+    if let Some(tenant) = args.tenant_id.clone() {
+        cfg.tenant_id = Some(tenant);
+    }
+    if let Some(header) = args.tenant_header.clone() {
+        cfg.tenant_header = header;
+    }
+    if !args.allowed_tenants.is_empty() {
+        cfg.allowed_tenants = args
+            .allowed_tenants
+            .iter()
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+    }
+    if let Some(flavor) = args.flavor {
+        cfg.flavor = flavor;
+    }
+    if !args.scrape_allowed_hosts.is_empty() {
+        cfg.scrape_allowed_hosts = args
+            .scrape_allowed_hosts
+            .iter()
+            .map(|h| h.trim().to_string())
+            .filter(|h| !h.is_empty())
+            .collect();
+    }
+    set_admin_tools_enabled(cfg.enable_admin_tools);
+    set_allowed_tenants(cfg.allowed_tenants.clone());
+    set_scrape_allowed_hosts(cfg.scrape_allowed_hosts.clone());
+    set_scrape_timeout(cfg.timeout);
+    set_backend_flavor(cfg.flavor);
 
-    match HttpPrometheusRepository::new(cfg) {
-        Ok(repo) => set_repository(Arc::new(repo)),
+    let repo: Arc<dyn PrometheusRepository> = match HttpPrometheusRepository::new(cfg.clone()) {
+        Ok(repo) => Arc::new(repo),
         Err(e) => {
             eprintln!("Failed to initialize Prometheus repository: {}", e);
             return;
         }
+    };
+    match cfg.remote_read_url.clone() {
+        Some(url) => match RemoteReadRepository::new(cfg, &url, repo) {
+            Ok(remote) => set_repository(Arc::new(remote)),
+            Err(e) => {
+                eprintln!("Failed to initialize remote read client: {}", e);
+                return;
+            }
+        },
+        None => set_repository(repo),
+    }
+
+    let am_env = match AlertmanagerConfig::from_env() {
+        Ok(am_env) => am_env,
+        Err(e) => {
+            eprintln!("Invalid Alertmanager configuration: {}", e);
+            return;
+        }
+    };
+    if let Some(url) = args.alertmanager_url.clone() {
+        let mut am_cfg = AlertmanagerConfig {
+            url,
+            ..am_env.unwrap_or_default()
+        };
+        if let Some(secs) = args.alertmanager_timeout {
+            am_cfg.timeout = Duration::from_secs(secs);
+        }
+        if let Some(user) = args.alertmanager_username.clone() {
+            am_cfg.username = Some(user);
+        }
+        if let Some(pass) = args.alertmanager_password.clone() {
+            am_cfg.password = Some(pass);
+        }
+        if args.enable_silence_tools {
+            am_cfg.enable_silences = true;
+        }
+        set_silence_tools_enabled(am_cfg.enable_silences);
+        match AlertmanagerClient::new(am_cfg) {
+            Ok(client) => set_alertmanager(Arc::new(client)),
+            Err(e) => {
+                eprintln!("Failed to initialize Alertmanager client: {}", e);
+                return;
+            }
+        }
     }
 
     if let Some(cmd) = &args.cmd {
@@ -223,14 +684,33 @@ async fn main() {
             }
             if let Ok(mut rpc_request) = Request::from_value(json_value) {
                 let id = rpc_request.id.clone();
+                let mut tenant = None;
                 if rpc_request.method == "tools/call" {
                     if let Some(raw_params) = rpc_request.params.take() {
-                        if let Ok(params) =
+                        if let Ok(mut params) =
                             serde_json::from_value::<ToolCallRequestParams>(raw_params)
                         {
                             if !params.name.is_empty() {
                                 metrics::record_tool_call(&params.name);
                             }
+                            // The tenant applies to every Prometheus request the tool makes
+                            match take_tenant_argument(&params.name, &mut params.arguments) {
+                                Ok(selected) => tenant = selected,
+                                Err(message) => {
+                                    let result = CallToolResult {
+                                        content: vec![CallToolResultContent::Text {
+                                            text: message,
+                                        }],
+                                        is_error: true,
+                                    };
+                                    let response = JsonRpcResponse::new(id, json!(result));
+                                    if let Ok(response_json) = serde_json::to_string(&response) {
+                                        let _ = writeln!(logging_file, "{}\n", &response_json);
+                                        println!("{}", response_json);
+                                    }
+                                    continue;
+                                }
+                            }
                             rpc_request = Request {
                                 id: id.clone(),
                                 method: params.name,
@@ -239,7 +719,7 @@ async fn main() {
                         }
                     }
                 }
-                match router.call(rpc_request).await {
+                match with_tenant(tenant, router.call(rpc_request)).await {
                     Ok(call_response) => {
                         if !call_response.value.is_null() {
                             let response = JsonRpcResponse::new(id, call_response.value.clone());
@@ -284,10 +764,24 @@ async fn main() {
 /// Execute a single CLI command using the same tool handlers as the MCP server.
 async fn run_cli_command(cmd: &PromCmd) {
     match cmd {
-        PromCmd::Query { query, time } => {
+        PromCmd::Query {
+            query,
+            time,
+            stats,
+            timeout,
+            limit,
+            thanos,
+        } => {
             let res = prometheus_query(PrometheusQueryRequest {
                 query: query.clone(),
                 time: time.clone(),
+                stats: Some(*stats),
+                timeout: timeout.clone(),
+                limit: *limit,
+                dedup: thanos.dedup,
+                partial_response: thanos.partial_response,
+                max_source_resolution: thanos.max_source_resolution.clone(),
+                engine: thanos.engine.clone(),
             })
             .await;
             print_tool_result(res);
@@ -297,12 +791,23 @@ async fn run_cli_command(cmd: &PromCmd) {
             start,
             end,
             step,
+            stats,
+            timeout,
+            limit,
+            thanos,
         } => {
             let res = prometheus_query_range(PrometheusQueryRangeRequest {
                 query: query.clone(),
                 start: start.clone(),
                 end: end.clone(),
                 step: step.clone(),
+                stats: Some(*stats),
+                timeout: timeout.clone(),
+                limit: *limit,
+                dedup: thanos.dedup,
+                partial_response: thanos.partial_response,
+                max_source_resolution: thanos.max_source_resolution.clone(),
+                engine: thanos.engine.clone(),
             })
             .await;
             print_tool_result(res);
@@ -311,23 +816,277 @@ async fn run_cli_command(cmd: &PromCmd) {
             let res = prometheus_list_metrics(PrometheusListMetricsRequest {}).await;
             print_tool_result(res);
         }
-        PromCmd::Metadata { metric } => {
+        PromCmd::Metadata {
+            metric,
+            limit,
+            limit_per_metric,
+        } => {
             let res = prometheus_get_metadata(PrometheusGetMetadataRequest {
                 metric: metric.clone(),
+                limit: *limit,
+                limit_per_metric: *limit_per_metric,
+            })
+            .await;
+            print_tool_result(res);
+        }
+        PromCmd::TargetsMetadata {
+            match_target,
+            metric,
+            limit,
+        } => {
+            let res = prometheus_get_targets_metadata(PrometheusGetTargetsMetadataRequest {
+                match_target: match_target.clone(),
+                metric: metric.clone(),
+                limit: *limit,
             })
             .await;
             print_tool_result(res);
         }
-        PromCmd::Series { selectors } => {
+        PromCmd::Series {
+            selectors,
+            start,
+            end,
+            limit,
+        } => {
             let res = prometheus_get_series(PrometheusGetSeriesRequest {
                 match_strings: selectors.clone(),
+                start: start.clone(),
+                end: end.clone(),
+                limit: *limit,
             })
             .await;
             print_tool_result(res);
         }
-        PromCmd::LabelValues { label_name } => {
+        PromCmd::LabelValues {
+            label_name,
+            selectors,
+            start,
+            end,
+            limit,
+        } => {
             let res = prometheus_get_label_values(PrometheusGetLabelValuesRequest {
                 label_name: label_name.clone(),
+                match_strings: Some(selectors.clone()),
+                start: start.clone(),
+                end: end.clone(),
+                limit: *limit,
+            })
+            .await;
+            print_tool_result(res);
+        }
+        PromCmd::ListLabels {
+            selectors,
+            start,
+            end,
+            limit,
+        } => {
+            let res = prometheus_list_labels(PrometheusListLabelsRequest {
+                match_strings: Some(selectors.clone()),
+                start: start.clone(),
+                end: end.clone(),
+                limit: *limit,
+            })
+            .await;
+            print_tool_result(res);
+        }
+        PromCmd::Alerts {
+            state,
+            alertname,
+            matchers,
+        } => {
+            let res = prometheus_get_alerts(PrometheusGetAlertsRequest {
+                state: state.clone(),
+                alertname: alertname.clone(),
+                matchers: Some(matchers.clone()),
+            })
+            .await;
+            print_tool_result(res);
+        }
+        PromCmd::Rules {
+            rule_type,
+            rule_names,
+            rule_groups,
+            files,
+        } => {
+            let res = prometheus_get_rules(PrometheusGetRulesRequest {
+                rule_type: rule_type.clone(),
+                rule_name: Some(rule_names.clone()),
+                rule_group: Some(rule_groups.clone()),
+                file: Some(files.clone()),
+            })
+            .await;
+            print_tool_result(res);
+        }
+        PromCmd::Status { section } => {
+            let res = prometheus_get_status(PrometheusGetStatusRequest {
+                section: section.clone(),
+            })
+            .await;
+            print_tool_result(res);
+        }
+        PromCmd::TsdbStatus { limit } => {
+            let res = prometheus_tsdb_status(PrometheusTsdbStatusRequest { limit: *limit }).await;
+            print_tool_result(res);
+        }
+        PromCmd::Exemplars { query, start, end } => {
+            let res = prometheus_query_exemplars(PrometheusQueryExemplarsRequest {
+                query: query.clone(),
+                start: start.clone(),
+                end: end.clone(),
+            })
+            .await;
+            print_tool_result(res);
+        }
+        PromCmd::FormatQuery { query } => {
+            let res = prometheus_format_query(PrometheusFormatQueryRequest {
+                query: query.clone(),
+            })
+            .await;
+            print_tool_result(res);
+        }
+        PromCmd::ParseQuery { query } => {
+            let res = prometheus_parse_query(PrometheusParseQueryRequest {
+                query: query.clone(),
+            })
+            .await;
+            print_tool_result(res);
+        }
+        PromCmd::ReadRaw {
+            selector,
+            start,
+            end,
+        } => {
+            let res = prometheus_read_raw(PrometheusReadRawRequest {
+                selector: selector.clone(),
+                start: start.clone(),
+                end: end.clone(),
+            })
+            .await;
+            print_tool_result(res);
+        }
+        PromCmd::Federate { selectors } => {
+            let res = prometheus_federate(PrometheusFederateRequest {
+                match_strings: selectors.clone(),
+            })
+            .await;
+            print_tool_result(res);
+        }
+        PromCmd::ScrapeTarget {
+            url,
+            metric,
+            include_samples,
+        } => {
+            // The operator typed this URL, so the allowlist for model-supplied URLs does not apply
+            set_scrape_allowed_hosts(vec!["*".to_string()]);
+            let res = prometheus_scrape_target(PrometheusScrapeTargetRequest {
+                url: url.clone(),
+                metric: metric.clone(),
+                include_samples: Some(*include_samples),
+            })
+            .await;
+            print_tool_result(res);
+        }
+        PromCmd::AmAlerts { filters, receiver } => {
+            let res = alertmanager_list_alerts(AlertmanagerAlertFilterRequest {
+                filter: Some(filters.clone()),
+                receiver: receiver.clone(),
+                active: None,
+                silenced: None,
+                inhibited: None,
+            })
+            .await;
+            print_tool_result(res);
+        }
+        PromCmd::AmGroups { filters, receiver } => {
+            let res = alertmanager_list_alert_groups(AlertmanagerAlertFilterRequest {
+                filter: Some(filters.clone()),
+                receiver: receiver.clone(),
+                active: None,
+                silenced: None,
+                inhibited: None,
+            })
+            .await;
+            print_tool_result(res);
+        }
+        PromCmd::AmStatus => {
+            let res = alertmanager_get_status(AlertmanagerGetStatusRequest {}).await;
+            print_tool_result(res);
+        }
+        PromCmd::AmSilences { filters, state } => {
+            let res = alertmanager_list_silences(AlertmanagerListSilencesRequest {
+                filter: Some(filters.clone()),
+                state: state.clone(),
+            })
+            .await;
+            print_tool_result(res);
+        }
+        PromCmd::AmSilenceCreate {
+            matchers,
+            duration,
+            comment,
+            created_by,
+            confirm,
+        } => {
+            let res = alertmanager_create_silence(AlertmanagerCreateSilenceRequest {
+                matchers: matchers.clone(),
+                duration: duration.clone(),
+                comment: comment.clone(),
+                created_by: created_by.clone(),
+                dry_run: Some(confirm.is_none()),
+                confirm: confirm.clone(),
+            })
+            .await;
+            print_tool_result(res);
+        }
+        PromCmd::AmSilenceExtend {
+            id,
+            duration,
+            confirm,
+        } => {
+            let res = alertmanager_extend_silence(AlertmanagerExtendSilenceRequest {
+                id: id.clone(),
+                duration: duration.clone(),
+                dry_run: Some(confirm.is_none()),
+                confirm: confirm.clone(),
+            })
+            .await;
+            print_tool_result(res);
+        }
+        PromCmd::AmSilenceExpire { id, confirm } => {
+            let res = alertmanager_expire_silence(AlertmanagerExpireSilenceRequest {
+                id: id.clone(),
+                dry_run: Some(confirm.is_none()),
+                confirm: confirm.clone(),
+            })
+            .await;
+            print_tool_result(res);
+        }
+        PromCmd::AdminSnapshot { skip_head, execute } => {
+            let res = prometheus_admin_snapshot(PrometheusAdminSnapshotRequest {
+                skip_head: Some(*skip_head),
+                dry_run: Some(!execute),
+            })
+            .await;
+            print_tool_result(res);
+        }
+        PromCmd::AdminDeleteSeries {
+            selectors,
+            start,
+            end,
+            execute,
+        } => {
+            let res = prometheus_admin_delete_series(PrometheusAdminDeleteSeriesRequest {
+                match_strings: selectors.clone(),
+                start: start.clone(),
+                end: end.clone(),
+                dry_run: Some(!execute),
+            })
+            .await;
+            print_tool_result(res);
+        }
+        PromCmd::AdminCleanTombstones { execute } => {
+            let res = prometheus_admin_clean_tombstones(PrometheusAdminCleanTombstonesRequest {
+                dry_run: Some(!execute),
             })
             .await;
             print_tool_result(res);
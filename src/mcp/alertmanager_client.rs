@@ -0,0 +1,627 @@
+//! Client for the Alertmanager v2 API.
+//!
+//! Prometheus only knows which alerts fire; Alertmanager knows where they were routed and
+//! whether they are silenced or inhibited. The client is registered globally when an
+//! Alertmanager URL is configured, and the Alertmanager tools are only offered then.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use reqwest::{Client, Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::mcp::alertmanager_config::AlertmanagerConfig;
+use crate::mcp::matchers::{LabelMatcher, MatchOp};
+use crate::mcp::prometheus_client::PrometheusError;
+
+/// Alertmanager API client
+pub struct AlertmanagerClient {
+    config: AlertmanagerConfig,
+    client: Client,
+}
+
+/// Receiver reference, as in `receivers` of an alert or `receiver` of a group
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Receiver {
+    pub name: String,
+}
+
+/// Processing state of an alert and what mutes it
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AlertStatus {
+    /// `unprocessed`, `active` or `suppressed`
+    pub state: String,
+    pub silenced_by: Vec<String>,
+    pub inhibited_by: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub muted_by: Vec<String>,
+}
+
+/// Alert as returned by `/api/v2/alerts`
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GettableAlert {
+    pub labels: HashMap<String, String>,
+    pub annotations: HashMap<String, String>,
+    pub receivers: Vec<Receiver>,
+    pub fingerprint: String,
+    pub starts_at: String,
+    pub updated_at: String,
+    pub ends_at: String,
+    #[serde(rename = "generatorURL", skip_serializing_if = "Option::is_none")]
+    pub generator_url: Option<String>,
+    pub status: AlertStatus,
+}
+
+/// Alert group as returned by `/api/v2/alerts/groups`
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AlertGroup {
+    pub labels: HashMap<String, String>,
+    pub receiver: Receiver,
+    pub alerts: Vec<GettableAlert>,
+}
+
+/// Cluster peer
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PeerStatus {
+    pub name: String,
+    pub address: String,
+}
+
+/// Cluster membership: `ready`, `settling` or `disabled`
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ClusterStatus {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub status: String,
+    pub peers: Vec<PeerStatus>,
+}
+
+/// Loaded configuration; `original` is the raw YAML
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AlertmanagerConfigStatus {
+    pub original: String,
+}
+
+/// Status as returned by `/api/v2/status`
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AlertmanagerStatus {
+    pub cluster: ClusterStatus,
+    pub version_info: HashMap<String, String>,
+    pub config: AlertmanagerConfigStatus,
+    pub uptime: String,
+}
+
+/// Silence matcher; `is_equal: false` negates it (`!=`, `!~`)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SilenceMatcher {
+    pub name: String,
+    pub value: String,
+    pub is_regex: bool,
+    #[serde(default = "default_is_equal")]
+    pub is_equal: bool,
+}
+
+fn default_is_equal() -> bool {
+    true
+}
+
+impl From<&LabelMatcher> for SilenceMatcher {
+    fn from(m: &LabelMatcher) -> Self {
+        Self {
+            name: m.name.clone(),
+            value: m.value.clone(),
+            is_regex: matches!(m.op, MatchOp::RegexMatch | MatchOp::RegexNoMatch),
+            is_equal: matches!(m.op, MatchOp::Equal | MatchOp::RegexMatch),
+        }
+    }
+}
+
+impl fmt::Display for SilenceMatcher {
+    /// Alert filter syntax, e.g. `severity!~"info|debug"`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match (self.is_equal, self.is_regex) {
+            (true, false) => MatchOp::Equal,
+            (false, false) => MatchOp::NotEqual,
+            (true, true) => MatchOp::RegexMatch,
+            (false, true) => MatchOp::RegexNoMatch,
+        };
+        write!(f, "{}{}{:?}", self.name, op, self.value)
+    }
+}
+
+/// Silence state: `active`, `pending` or `expired`
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SilenceStatus {
+    pub state: String,
+}
+
+/// Silence as returned by `/api/v2/silences`; without `id` (and `status`) it is the body
+/// of a create request, with `id` an update of that silence
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Silence {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<SilenceStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+    pub matchers: Vec<SilenceMatcher>,
+    pub starts_at: String,
+    pub ends_at: String,
+    pub created_by: String,
+    pub comment: String,
+}
+
+impl Silence {
+    /// `active`, `pending` or `expired`; empty for a silence not yet created
+    pub fn state(&self) -> &str {
+        self.status.as_ref().map(|s| s.state.as_str()).unwrap_or("")
+    }
+}
+
+#[derive(Deserialize)]
+struct PostSilenceResponse {
+    #[serde(rename = "silenceID")]
+    silence_id: String,
+}
+
+/// Filters shared by the alerts and alert groups endpoints
+#[derive(Debug, Clone, Default)]
+pub struct AlertFilter {
+    /// Label matchers such as `alertname="Foo"` or `severity=~"critical|page"`
+    pub filter: Vec<String>,
+    /// Regex matched against receiver names
+    pub receiver: Option<String>,
+    pub active: Option<bool>,
+    pub silenced: Option<bool>,
+    pub inhibited: Option<bool>,
+}
+
+impl AlertFilter {
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params: Vec<(&str, String)> =
+            self.filter.iter().map(|f| ("filter", f.clone())).collect();
+        if let Some(r) = &self.receiver {
+            params.push(("receiver", r.clone()));
+        }
+        for (name, value) in [
+            ("active", self.active),
+            ("silenced", self.silenced),
+            ("inhibited", self.inhibited),
+        ] {
+            if let Some(v) = value {
+                params.push((name, v.to_string()));
+            }
+        }
+        params
+    }
+}
+
+impl AlertmanagerClient {
+    /// Create a new AlertmanagerClient with the given configuration
+    pub fn new(config: AlertmanagerConfig) -> Result<Self, PrometheusError> {
+        let client = Client::builder()
+            .timeout(config.timeout)
+            .build()
+            .map_err(|e| PrometheusError::BuildClientError(e.to_string()))?;
+        Ok(Self { config, client })
+    }
+
+    /// Build a request, applying basic auth if configured
+    fn build_request(&self, method: Method, url: &str) -> RequestBuilder {
+        let rb = self.client.request(method, url);
+        match (&self.config.username, &self.config.password) {
+            (Some(user), Some(pass)) => rb.basic_auth(user, Some(pass)),
+            _ => rb,
+        }
+    }
+
+    fn build_get(&self, url: &str) -> RequestBuilder {
+        self.build_request(Method::GET, url)
+    }
+
+    /// Send a request, mapping error statuses
+    async fn send(&self, rb: RequestBuilder) -> Result<Response, PrometheusError> {
+        let response = rb.send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(api_error(status, text.trim()));
+        }
+        Ok(response)
+    }
+
+    /// Send a request and decode the JSON body
+    async fn send_json<T: DeserializeOwned>(
+        &self,
+        rb: RequestBuilder,
+    ) -> Result<T, PrometheusError> {
+        self.send(rb).await?.json().await.map_err(|e| {
+            PrometheusError::ParseError(format!("Failed to parse Alertmanager response: {}", e))
+        })
+    }
+
+    /// List alerts with their receivers and silence/inhibition status
+    pub async fn get_alerts(
+        &self,
+        filter: &AlertFilter,
+    ) -> Result<Vec<GettableAlert>, PrometheusError> {
+        let url = format!("{}/api/v2/alerts", self.config.url);
+        self.send_json(self.build_get(&url).query(&filter.params()))
+            .await
+    }
+
+    /// List alerts grouped by route and receiver
+    pub async fn get_alert_groups(
+        &self,
+        filter: &AlertFilter,
+    ) -> Result<Vec<AlertGroup>, PrometheusError> {
+        let url = format!("{}/api/v2/alerts/groups", self.config.url);
+        self.send_json(self.build_get(&url).query(&filter.params()))
+            .await
+    }
+
+    /// Get cluster, version and configuration status
+    pub async fn get_status(&self) -> Result<AlertmanagerStatus, PrometheusError> {
+        let url = format!("{}/api/v2/status", self.config.url);
+        self.send_json(self.build_get(&url)).await
+    }
+
+    /// List silences, including expired ones still retained by Alertmanager
+    pub async fn get_silences(&self, filter: &[String]) -> Result<Vec<Silence>, PrometheusError> {
+        let url = format!("{}/api/v2/silences", self.config.url);
+        let params: Vec<(&str, &str)> = filter.iter().map(|f| ("filter", f.as_str())).collect();
+        self.send_json(self.build_get(&url).query(&params)).await
+    }
+
+    /// URL of the silence `id`, escaped so it stays a single path segment
+    fn silence_url(&self, id: &str) -> Result<Url, PrometheusError> {
+        if id.is_empty() || id == "." || id == ".." {
+            return Err(PrometheusError::BadData(format!(
+                "invalid silence ID: {:?}",
+                id
+            )));
+        }
+        let mut url = Url::parse(&format!("{}/api/v2/silence", self.config.url)).map_err(|e| {
+            PrometheusError::BuildClientError(format!("invalid Alertmanager URL: {}", e))
+        })?;
+        url.path_segments_mut()
+            .map_err(|_| PrometheusError::BuildClientError("invalid Alertmanager URL".to_string()))?
+            .push(id);
+        Ok(url)
+    }
+
+    /// Get a single silence by ID
+    pub async fn get_silence(&self, id: &str) -> Result<Silence, PrometheusError> {
+        let url = self.silence_url(id)?;
+        self.send_json(self.build_get(url.as_str())).await
+    }
+
+    /// Create a silence, or update the one with `silence.id`; returns the silence ID
+    pub async fn post_silence(&self, silence: &Silence) -> Result<String, PrometheusError> {
+        let url = format!("{}/api/v2/silences", self.config.url);
+        let body = Silence {
+            status: None,
+            updated_at: None,
+            ..silence.clone()
+        };
+        let created: PostSilenceResponse = self
+            .send_json(self.build_request(Method::POST, &url).json(&body))
+            .await?;
+        Ok(created.silence_id)
+    }
+
+    /// Expire a silence immediately
+    pub async fn expire_silence(&self, id: &str) -> Result<(), PrometheusError> {
+        let url = self.silence_url(id)?;
+        self.send(self.build_request(Method::DELETE, url.as_str()))
+            .await?;
+        Ok(())
+    }
+}
+
+/// Parse a Prometheus-style duration such as `30m`, `2h` or `1h30m` into milliseconds
+pub fn parse_duration_ms(input: &str) -> Result<i64, String> {
+    let invalid = || {
+        format!(
+            "invalid duration: {:?} (expected e.g. 30m, 2h, 1h30m)",
+            input
+        )
+    };
+    let s = input.trim();
+    if s.is_empty() {
+        return Err(invalid());
+    }
+    let mut total: i64 = 0;
+    let mut rest = s;
+    while !rest.is_empty() {
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        let number: i64 = rest[..digits].parse().map_err(|_| invalid())?;
+        rest = &rest[digits..];
+        let unit_len = rest.bytes().take_while(|b| b.is_ascii_alphabetic()).count();
+        let unit_ms: i64 = match &rest[..unit_len] {
+            "ms" => 1,
+            "s" => 1_000,
+            "m" => 60_000,
+            "h" => 3_600_000,
+            "d" => 86_400_000,
+            "w" => 604_800_000,
+            "y" => 31_536_000_000,
+            _ => return Err(invalid()),
+        };
+        rest = &rest[unit_len..];
+        total = number
+            .checked_mul(unit_ms)
+            .and_then(|ms| total.checked_add(ms))
+            .ok_or_else(invalid)?;
+    }
+    Ok(total)
+}
+
+/// Map an Alertmanager error response to a typed error
+fn api_error(status: StatusCode, body: &str) -> PrometheusError {
+    // Errors are either a JSON string or `{"code": ..., "message": ...}`
+    let message = serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|v| match v {
+            serde_json::Value::String(s) => Some(s),
+            v => v
+                .get("message")
+                .and_then(|m| m.as_str())
+                .map(str::to_string),
+        })
+        .unwrap_or_else(|| body.to_string());
+    match status {
+        StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => {
+            PrometheusError::BadData(message)
+        }
+        StatusCode::NOT_FOUND => PrometheusError::NotFound(message),
+        _ => PrometheusError::ApiError(format!(
+            "Alertmanager API error {}: {}",
+            status.as_u16(),
+            message
+        )),
+    }
+}
+
+#[async_trait]
+pub trait AlertmanagerRepository: Send + Sync {
+    async fn get_alerts(&self, filter: &AlertFilter)
+        -> Result<Vec<GettableAlert>, PrometheusError>;
+    async fn get_alert_groups(
+        &self,
+        filter: &AlertFilter,
+    ) -> Result<Vec<AlertGroup>, PrometheusError>;
+    async fn get_status(&self) -> Result<AlertmanagerStatus, PrometheusError>;
+    async fn get_silences(&self, filter: &[String]) -> Result<Vec<Silence>, PrometheusError>;
+    async fn get_silence(&self, id: &str) -> Result<Silence, PrometheusError>;
+    async fn post_silence(&self, silence: &Silence) -> Result<String, PrometheusError>;
+    async fn expire_silence(&self, id: &str) -> Result<(), PrometheusError>;
+}
+
+#[async_trait]
+impl AlertmanagerRepository for AlertmanagerClient {
+    async fn get_alerts(
+        &self,
+        filter: &AlertFilter,
+    ) -> Result<Vec<GettableAlert>, PrometheusError> {
+        AlertmanagerClient::get_alerts(self, filter).await
+    }
+
+    async fn get_alert_groups(
+        &self,
+        filter: &AlertFilter,
+    ) -> Result<Vec<AlertGroup>, PrometheusError> {
+        AlertmanagerClient::get_alert_groups(self, filter).await
+    }
+
+    async fn get_status(&self) -> Result<AlertmanagerStatus, PrometheusError> {
+        AlertmanagerClient::get_status(self).await
+    }
+
+    async fn get_silences(&self, filter: &[String]) -> Result<Vec<Silence>, PrometheusError> {
+        AlertmanagerClient::get_silences(self, filter).await
+    }
+
+    async fn get_silence(&self, id: &str) -> Result<Silence, PrometheusError> {
+        AlertmanagerClient::get_silence(self, id).await
+    }
+
+    async fn post_silence(&self, silence: &Silence) -> Result<String, PrometheusError> {
+        AlertmanagerClient::post_silence(self, silence).await
+    }
+
+    async fn expire_silence(&self, id: &str) -> Result<(), PrometheusError> {
+        AlertmanagerClient::expire_silence(self, id).await
+    }
+}
+
+static ALERTMANAGER: Lazy<RwLock<Option<Arc<dyn AlertmanagerRepository>>>> =
+    Lazy::new(|| RwLock::new(None));
+
+/// The configured Alertmanager, built from the environment on first use, or `None` when
+/// no Alertmanager URL is configured
+pub fn get_alertmanager() -> Option<Arc<dyn AlertmanagerRepository>> {
+    if let Some(repo) = ALERTMANAGER.read().unwrap().as_ref() {
+        return Some(Arc::clone(repo));
+    }
+    let client = AlertmanagerClient::new(AlertmanagerConfig::from_env().ok()??).ok()?;
+    let arc: Arc<dyn AlertmanagerRepository> = Arc::new(client);
+    *ALERTMANAGER.write().unwrap() = Some(Arc::clone(&arc));
+    Some(arc)
+}
+
+/// Override the Alertmanager instance (DI for tests or custom setups)
+pub fn set_alertmanager(repo: Arc<dyn AlertmanagerRepository>) {
+    *ALERTMANAGER.write().unwrap() = Some(repo);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::test_server::{serve_once, Response};
+    use serde_json::Value;
+
+    #[tokio::test]
+    async fn test_get_alerts_with_filter() {
+        let body = r#"[{
+            "labels": {"alertname": "HighLatency", "severity": "page"},
+            "annotations": {"summary": "p99 above 1s"},
+            "receivers": [{"name": "pagerduty"}],
+            "fingerprint": "a1b2c3",
+            "startsAt": "2025-09-27T12:00:00Z",
+            "updatedAt": "2025-09-27T12:01:00Z",
+            "endsAt": "2025-09-27T12:05:00Z",
+            "generatorURL": "http://prom/graph",
+            "status": {"state": "suppressed", "silencedBy": [], "inhibitedBy": ["d4e5f6"]}
+        }]"#;
+        let (url, server) = serve_once(Response::json(body)).await;
+
+        let cfg = AlertmanagerConfig::with_url(url).with_basic_auth("u", "p");
+        let client = AlertmanagerClient::new(cfg).unwrap();
+        let alerts = client
+            .get_alerts(&AlertFilter {
+                filter: vec!["severity=\"page\"".to_string()],
+                inhibited: Some(true),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].receivers[0].name, "pagerduty");
+        assert_eq!(alerts[0].status.state, "suppressed");
+        assert_eq!(alerts[0].status.inhibited_by, vec!["d4e5f6"]);
+        assert_eq!(
+            alerts[0].generator_url.as_deref(),
+            Some("http://prom/graph")
+        );
+
+        let request = server.await.unwrap();
+        assert!(request
+            .head
+            .starts_with("GET /api/v2/alerts?filter=severity%3D%22page%22&inhibited=true "));
+        assert!(request
+            .header("authorization")
+            .unwrap()
+            .starts_with("Basic "));
+    }
+
+    #[tokio::test]
+    async fn test_error_response() {
+        let (url, server) = serve_once(
+            Response::new("400 Bad Request", r#""bad matcher format: severity""#)
+                .header("Content-Type", "application/json"),
+        )
+        .await;
+
+        let client = AlertmanagerClient::new(AlertmanagerConfig::with_url(url)).unwrap();
+        let err = client.get_status().await.unwrap_err();
+        assert!(
+            matches!(err, PrometheusError::BadData(ref m) if m == "bad matcher format: severity")
+        );
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_post_and_expire_silence() {
+        let (url, server) = serve_once(Response::json(r#"{"silenceID":"7f3c"}"#)).await;
+
+        let client = AlertmanagerClient::new(AlertmanagerConfig::with_url(url)).unwrap();
+        let matchers = crate::mcp::matchers::parse_matchers(r#"alertname="Disk", env!~"dev|ci""#)
+            .unwrap()
+            .iter()
+            .map(SilenceMatcher::from)
+            .collect();
+        let silence = Silence {
+            matchers,
+            starts_at: "2025-09-27T12:00:00Z".into(),
+            ends_at: "2025-09-27T14:00:00Z".into(),
+            created_by: "oncall".into(),
+            comment: "disk replacement".into(),
+            ..Default::default()
+        };
+        assert_eq!(client.post_silence(&silence).await.unwrap(), "7f3c");
+        let request = server.await.unwrap();
+        assert!(request.head.starts_with("POST /api/v2/silences "));
+        let sent: Value = serde_json::from_slice(&request.body).unwrap();
+        assert!(sent.get("id").is_none());
+        assert_eq!(sent["matchers"][1]["isRegex"], true);
+        assert_eq!(sent["matchers"][1]["isEqual"], false);
+
+        let (url, server) = serve_once(Response::new("200 OK", "")).await;
+        let client = AlertmanagerClient::new(AlertmanagerConfig::with_url(url)).unwrap();
+        client.expire_silence("7f3c").await.unwrap();
+        assert!(server
+            .await
+            .unwrap()
+            .head
+            .starts_with("DELETE /api/v2/silence/7f3c "));
+
+        // IDs cannot escape the silence path
+        let client =
+            AlertmanagerClient::new(AlertmanagerConfig::with_url("http://am:9093".into())).unwrap();
+        assert_eq!(
+            client.silence_url("../alerts?x=1#y").unwrap().as_str(),
+            "http://am:9093/api/v2/silence/..%2Falerts%3Fx=1%23y"
+        );
+        assert!(client.silence_url("..").is_err());
+    }
+
+    #[test]
+    fn test_silence_matcher_display() {
+        let m = SilenceMatcher {
+            name: "env".into(),
+            value: "dev|ci".into(),
+            is_regex: true,
+            is_equal: false,
+        };
+        assert_eq!(m.to_string(), r#"env!~"dev|ci""#);
+        let parsed: SilenceMatcher =
+            serde_json::from_str(r#"{"name":"job","value":"node","isRegex":false}"#).unwrap();
+        assert!(parsed.is_equal);
+    }
+
+    #[test]
+    fn test_parse_duration_ms() {
+        assert_eq!(parse_duration_ms("30m"), Ok(1_800_000));
+        assert_eq!(parse_duration_ms("1h30m"), Ok(5_400_000));
+        assert_eq!(parse_duration_ms("2d"), Ok(172_800_000));
+        assert_eq!(parse_duration_ms("500ms"), Ok(500));
+        assert!(parse_duration_ms("").is_err());
+        assert!(parse_duration_ms("2").is_err());
+        assert!(parse_duration_ms("h").is_err());
+        assert!(parse_duration_ms("1.5h").is_err());
+    }
+
+    #[test]
+    fn test_parse_status() {
+        let raw = r#"{
+            "cluster": {"name": "01J", "status": "ready", "peers": [{"name": "01J", "address": "10.0.0.1:9094"}]},
+            "versionInfo": {"version": "0.28.0", "revision": "abc"},
+            "config": {"original": "global:\n  resolve_timeout: 5m\n"},
+            "uptime": "2025-09-27T10:00:00Z"
+        }"#;
+        let status: AlertmanagerStatus = serde_json::from_str(raw).unwrap();
+        assert_eq!(status.cluster.status, "ready");
+        assert_eq!(status.cluster.peers[0].address, "10.0.0.1:9094");
+        assert_eq!(status.version_info["version"], "0.28.0");
+    }
+}
@@ -0,0 +1,173 @@
+use std::env;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::mcp::prometheus_client::PrometheusError;
+
+/// Configuration for Alertmanager
+#[derive(Debug, Clone)]
+pub struct AlertmanagerConfig {
+    /// URL of the Alertmanager server
+    pub url: String,
+    /// Timeout for Alertmanager API requests in seconds
+    pub timeout: Duration,
+    /// Basic auth username
+    pub username: Option<String>,
+    /// Basic auth password
+    pub password: Option<String>,
+    /// Register the tools that create, extend and expire silences
+    pub enable_silences: bool,
+}
+
+impl Default for AlertmanagerConfig {
+    fn default() -> Self {
+        Self {
+            url: "http://localhost:9093".to_string(),
+            timeout: Duration::from_secs(10),
+            username: None,
+            password: None,
+            enable_silences: false,
+        }
+    }
+}
+
+impl AlertmanagerConfig {
+    /// Create an AlertmanagerConfig from environment variables, or `None` when
+    /// `ALERTMANAGER_URL` is not set. Invalid values are an error even without a URL,
+    /// since `--alertmanager-url` may supply it.
+    pub fn from_env() -> Result<Option<Self>, PrometheusError> {
+        let timeout_secs = match env::var("ALERTMANAGER_TIMEOUT") {
+            Ok(s) => parse_value("ALERTMANAGER_TIMEOUT", &s)?,
+            Err(_) => 10,
+        };
+        let enable_silences = match env::var("ALERTMANAGER_ENABLE_SILENCES") {
+            Ok(s) => parse_value("ALERTMANAGER_ENABLE_SILENCES", &s)?,
+            Err(_) => false,
+        };
+
+        let Ok(url) = env::var("ALERTMANAGER_URL") else {
+            return Ok(None);
+        };
+        let username = env::var("ALERTMANAGER_USERNAME").ok();
+        let password = env::var("ALERTMANAGER_PASSWORD").ok();
+
+        Ok(Some(Self {
+            url,
+            timeout: Duration::from_secs(timeout_secs),
+            username,
+            password,
+            enable_silences,
+        }))
+    }
+
+    /// Create an AlertmanagerConfig from a map of values (useful for tests)
+    #[cfg(test)]
+    pub fn from_map(
+        map: &std::collections::HashMap<&str, &str>,
+    ) -> Result<Option<Self>, PrometheusError> {
+        let timeout_secs = match map.get("ALERTMANAGER_TIMEOUT") {
+            Some(s) => parse_value("ALERTMANAGER_TIMEOUT", s)?,
+            None => 10,
+        };
+        let enable_silences = match map.get("ALERTMANAGER_ENABLE_SILENCES") {
+            Some(s) => parse_value("ALERTMANAGER_ENABLE_SILENCES", s)?,
+            None => false,
+        };
+
+        let Some(url) = map.get("ALERTMANAGER_URL") else {
+            return Ok(None);
+        };
+        let username = map.get("ALERTMANAGER_USERNAME").map(|s| s.to_string());
+        let password = map.get("ALERTMANAGER_PASSWORD").map(|s| s.to_string());
+
+        Ok(Some(Self {
+            url: url.to_string(),
+            timeout: Duration::from_secs(timeout_secs),
+            username,
+            password,
+            enable_silences,
+        }))
+    }
+
+    /// Create a new AlertmanagerConfig with the given URL
+    #[allow(dead_code)]
+    pub fn with_url(url: String) -> Self {
+        Self {
+            url,
+            ..Default::default()
+        }
+    }
+
+    /// Set the request timeout
+    #[allow(dead_code)]
+    pub fn with_timeout(mut self, timeout_secs: u64) -> Self {
+        self.timeout = Duration::from_secs(timeout_secs);
+        self
+    }
+
+    /// Set basic auth
+    #[allow(dead_code)]
+    pub fn with_basic_auth(
+        mut self,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        self.username = Some(username.into());
+        self.password = Some(password.into());
+        self
+    }
+}
+
+fn parse_value<T>(name: &str, s: &str) -> Result<T, PrometheusError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    s.trim()
+        .parse::<T>()
+        .map_err(|e| PrometheusError::BuildClientError(format!("invalid {} {:?}: {}", name, s, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_from_env_unset_and_overrides() {
+        let empty: HashMap<&str, &str> = HashMap::new();
+        assert!(AlertmanagerConfig::from_map(&empty).unwrap().is_none());
+
+        let mut vars: HashMap<&str, &str> = HashMap::new();
+        vars.insert("ALERTMANAGER_URL", "http://am:9093");
+        let cfg = AlertmanagerConfig::from_map(&vars).unwrap().unwrap();
+        assert_eq!(cfg.timeout, Duration::from_secs(10));
+        assert!(cfg.username.is_none());
+        assert!(!cfg.enable_silences);
+
+        vars.insert("ALERTMANAGER_TIMEOUT", "3");
+        vars.insert("ALERTMANAGER_USERNAME", "u");
+        vars.insert("ALERTMANAGER_PASSWORD", "p");
+        vars.insert("ALERTMANAGER_ENABLE_SILENCES", "true");
+        let cfg = AlertmanagerConfig::from_map(&vars).unwrap().unwrap();
+        assert_eq!(cfg.url, "http://am:9093");
+        assert_eq!(cfg.timeout, Duration::from_secs(3));
+        assert_eq!(cfg.username.as_deref(), Some("u"));
+        assert_eq!(cfg.password.as_deref(), Some("p"));
+        assert!(cfg.enable_silences);
+
+        // Invalid values fail loading instead of falling back to the defaults
+        for (name, value) in [
+            ("ALERTMANAGER_TIMEOUT", "10s"),
+            ("ALERTMANAGER_ENABLE_SILENCES", "yes"),
+        ] {
+            let mut vars = vars.clone();
+            vars.insert(name, value);
+            let err = AlertmanagerConfig::from_map(&vars).unwrap_err();
+            assert!(err.to_string().contains(name), "{}", err);
+            vars.remove("ALERTMANAGER_URL");
+            assert!(AlertmanagerConfig::from_map(&vars).is_err());
+        }
+    }
+}
@@ -0,0 +1,216 @@
+//! Request authorization for Prometheus: basic auth or a bearer token.
+//!
+//! A bearer token may come from a file, such as a Kubernetes service account token. The
+//! file is re-read when its modification time changes and at least once per refresh
+//! interval, so rotated tokens keep working without a restart. The modification time is
+//! checked at most once per second, so most requests do no file system work.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+use reqwest::RequestBuilder;
+
+use crate::mcp::prometheus_client::PrometheusError;
+use crate::mcp::prometheus_config::PrometheusConfig;
+
+/// Minimum time between two checks of the token file's modification time
+const MODIFIED_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Credentials attached to every Prometheus request
+pub enum Authorization {
+    None,
+    Basic { username: String, password: String },
+    Bearer(String),
+    BearerFile(TokenFile),
+}
+
+impl Authorization {
+    /// Pick the credentials from `config`; like Prometheus, at most one kind may be set
+    pub fn from_config(config: &PrometheusConfig) -> Result<Self, PrometheusError> {
+        let basic = match (&config.username, &config.password) {
+            (Some(username), Some(password)) => Some((username.clone(), password.clone())),
+            _ => None,
+        };
+        match (basic, &config.bearer_token, &config.bearer_token_file) {
+            (None, None, None) => Ok(Authorization::None),
+            (Some((username, password)), None, None) => {
+                Ok(Authorization::Basic { username, password })
+            }
+            (None, Some(token), None) => Ok(Authorization::Bearer(token.trim().to_string())),
+            (None, None, Some(path)) => Ok(Authorization::BearerFile(TokenFile::open(
+                path,
+                config.bearer_token_refresh,
+            )?)),
+            _ => Err(PrometheusError::BuildClientError(
+                "at most one of basic auth, bearer token and bearer token file may be configured"
+                    .to_string(),
+            )),
+        }
+    }
+
+    /// Add the `Authorization` header to `rb`
+    pub fn apply(&self, rb: RequestBuilder) -> RequestBuilder {
+        match self {
+            Authorization::None => rb,
+            Authorization::Basic { username, password } => rb.basic_auth(username, Some(password)),
+            Authorization::Bearer(token) => rb.bearer_auth(token),
+            Authorization::BearerFile(file) => rb.bearer_auth(file.token()),
+        }
+    }
+}
+
+/// Secret (a bearer token or header value) read from a file and reloaded when it changes
+pub struct TokenFile {
+    path: PathBuf,
+    refresh: Duration,
+    state: Mutex<TokenState>,
+}
+
+struct TokenState {
+    token: String,
+    modified: Option<SystemTime>,
+    loaded_at: Instant,
+    checked_at: Instant,
+}
+
+impl TokenFile {
+    /// Read the token once; a missing or empty file is an error
+    pub fn open(path: impl AsRef<Path>, refresh: Duration) -> Result<Self, PrometheusError> {
+        let path = path.as_ref().to_path_buf();
+        let (token, modified) = read_token(&path)?;
+        Ok(Self {
+            path,
+            refresh,
+            state: Mutex::new(TokenState {
+                token,
+                modified,
+                loaded_at: Instant::now(),
+                checked_at: Instant::now(),
+            }),
+        })
+    }
+
+    /// Current token, re-read from the file if it changed or the refresh interval elapsed.
+    /// If re-reading fails, the last token read is kept.
+    pub fn token(&self) -> String {
+        let mut state = self.state.lock().unwrap();
+        let refresh_due = state.loaded_at.elapsed() >= self.refresh;
+        if !refresh_due && state.checked_at.elapsed() < MODIFIED_CHECK_INTERVAL {
+            return state.token.clone();
+        }
+        state.checked_at = Instant::now();
+        let modified = fs::metadata(&self.path).and_then(|m| m.modified()).ok();
+        let changed = modified.is_some() && modified != state.modified;
+        if changed || refresh_due {
+            if let Ok((token, modified)) = read_token(&self.path) {
+                state.token = token;
+                state.modified = modified;
+            }
+            state.loaded_at = Instant::now();
+        }
+        state.token.clone()
+    }
+}
+
+fn read_token(path: &Path) -> Result<(String, Option<SystemTime>), PrometheusError> {
+    let content = fs::read_to_string(path).map_err(|e| {
+        PrometheusError::BuildClientError(format!("cannot read file {}: {}", path.display(), e))
+    })?;
+    let token = content.trim().to_string();
+    if token.is_empty() {
+        return Err(PrometheusError::BuildClientError(format!(
+            "file {} is empty",
+            path.display()
+        )));
+    }
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok();
+    Ok((token, modified))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_token_file(name: &str, content: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("prometheus-mcp-{}-{}", name, std::process::id()));
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_token_file_reload() {
+        let path = temp_token_file("token-reload", "first\n");
+        let file = TokenFile::open(&path, Duration::ZERO).unwrap();
+        assert_eq!(file.token(), "first");
+
+        fs::write(&path, "second").unwrap();
+        assert_eq!(file.token(), "second");
+
+        // A failed re-read keeps the last token
+        fs::write(&path, "  \n").unwrap();
+        assert_eq!(file.token(), "second");
+        fs::remove_file(&path).unwrap();
+        assert_eq!(file.token(), "second");
+    }
+
+    #[test]
+    fn test_token_file_waits_for_refresh_interval() {
+        let path = temp_token_file("token-interval", "first");
+        let file = TokenFile::open(&path, Duration::from_secs(3600)).unwrap();
+        {
+            // Same modification time as the loaded token: the file is not re-read yet
+            let mut state = file.state.lock().unwrap();
+            state.token = "cached".to_string();
+            state.checked_at -= MODIFIED_CHECK_INTERVAL;
+        }
+        assert_eq!(file.token(), "cached");
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_token_file_reloads_when_modified() {
+        let path = temp_token_file("token-modified", "first");
+        let file = TokenFile::open(&path, Duration::from_secs(3600)).unwrap();
+
+        fs::write(&path, "second").unwrap();
+        let modified = SystemTime::now() + Duration::from_secs(10);
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        // Checked less than a second ago: the file is not looked at yet
+        assert_eq!(file.token(), "first");
+
+        file.state.lock().unwrap().checked_at -= MODIFIED_CHECK_INTERVAL;
+        assert_eq!(file.token(), "second");
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_from_config() {
+        let cfg = PrometheusConfig::default();
+        assert!(matches!(
+            Authorization::from_config(&cfg).unwrap(),
+            Authorization::None
+        ));
+
+        let cfg = PrometheusConfig::default().with_bearer_token(" abc\n");
+        match Authorization::from_config(&cfg).unwrap() {
+            Authorization::Bearer(token) => assert_eq!(token, "abc"),
+            _ => panic!("expected bearer token"),
+        }
+
+        let cfg = PrometheusConfig::default()
+            .with_basic_auth("u", "p")
+            .with_bearer_token("abc");
+        assert!(Authorization::from_config(&cfg).is_err());
+
+        let cfg = PrometheusConfig::default().with_bearer_token_file("/nonexistent/token");
+        assert!(Authorization::from_config(&cfg).is_err());
+    }
+}
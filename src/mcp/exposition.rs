@@ -0,0 +1,448 @@
+//! Parsers for the Prometheus text exposition format (`text/plain; version=0.0.4`), as served
+//! by `/federate` and by exporters' `/metrics` endpoints, and for OpenMetrics
+//! (`application/openmetrics-text`).
+
+use std::collections::HashMap;
+
+use serde::{Serialize, Serializer};
+
+use crate::mcp::prometheus_client::format_sample_value;
+
+const TEXT_TYPES: &[&str] = &["counter", "gauge", "histogram", "summary", "untyped"];
+const OPENMETRICS_TYPES: &[&str] = &[
+    "counter",
+    "gauge",
+    "histogram",
+    "gaugehistogram",
+    "stateset",
+    "info",
+    "summary",
+    "unknown",
+];
+
+/// Exposition format of a scrape body
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    /// Prometheus text format; timestamps are integer milliseconds
+    Text,
+    /// OpenMetrics; timestamps are (fractional) seconds and lines may carry exemplars
+    OpenMetrics,
+}
+
+/// A metric family: its `# TYPE` and `# HELP` metadata and the samples that belong to it
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricFamily {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub help: Option<String>,
+    /// OpenMetrics `# UNIT`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    pub samples: Vec<Sample>,
+}
+
+/// One exposition line, e.g. `http_requests_total{code="200"} 1027 1395066363000`
+#[derive(Debug, Clone, Serialize)]
+pub struct Sample {
+    pub name: String,
+    pub labels: HashMap<String, String>,
+    /// Serialized like the HTTP API (`"1027"`, `"NaN"`, `"+Inf"`)
+    #[serde(serialize_with = "serialize_value")]
+    pub value: f64,
+    /// Milliseconds since the epoch, when the line carries a timestamp
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp_ms: Option<i64>,
+}
+
+fn serialize_value<S: Serializer>(v: &f64, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(&format_sample_value(*v))
+}
+
+/// Suffixes of the samples that make up a family of the given type besides its bare name
+fn family_suffixes(type_name: &str) -> &'static [&'static str] {
+    match type_name {
+        "counter" => &["_total", "_created"],
+        "histogram" => &["_bucket", "_sum", "_count", "_created"],
+        "gaugehistogram" => &["_bucket", "_gsum", "_gcount"],
+        "summary" => &["_sum", "_count", "_created"],
+        "info" => &["_info"],
+        _ => &[],
+    }
+}
+
+/// Parse a text-format exposition into metric families, in the order they first appear.
+///
+/// Samples without a preceding `# TYPE` line form their own `untyped` family.
+pub fn parse_text_format(input: &str) -> Result<Vec<MetricFamily>, String> {
+    parse(input, Format::Text)
+}
+
+/// Parse an OpenMetrics exposition into metric families. Parsing stops at `# EOF`,
+/// timestamps are converted to milliseconds and exemplars are skipped.
+pub fn parse_openmetrics(input: &str) -> Result<Vec<MetricFamily>, String> {
+    parse(input, Format::OpenMetrics)
+}
+
+/// Parse an exposition in the given format
+pub fn parse(input: &str, format: Format) -> Result<Vec<MetricFamily>, String> {
+    let (types, default_type) = match format {
+        Format::Text => (TEXT_TYPES, "untyped"),
+        Format::OpenMetrics => (OPENMETRICS_TYPES, "unknown"),
+    };
+    let mut families: Vec<MetricFamily> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    fn family<'a>(
+        families: &'a mut Vec<MetricFamily>,
+        index: &mut HashMap<String, usize>,
+        name: &str,
+        default_type: &str,
+    ) -> &'a mut MetricFamily {
+        let i = *index.entry(name.to_string()).or_insert_with(|| {
+            families.push(MetricFamily {
+                name: name.to_string(),
+                type_name: default_type.to_string(),
+                help: None,
+                unit: None,
+                samples: Vec::new(),
+            });
+            families.len() - 1
+        });
+        &mut families[i]
+    }
+
+    for (n, line) in input.lines().enumerate() {
+        let line = line.trim_end_matches('\r').trim_start();
+        let err = |msg: &str| format!("line {}: {}", n + 1, msg);
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(comment) = line.strip_prefix('#') {
+            let mut parts = comment.trim_start().splitn(3, [' ', '\t']);
+            let keyword = parts.next().unwrap_or_default();
+            if format == Format::OpenMetrics && keyword == "EOF" {
+                break;
+            }
+            if !matches!(keyword, "HELP" | "TYPE" | "UNIT") {
+                continue;
+            }
+            let name = parts.next().filter(|s| !s.is_empty());
+            let name = name.ok_or_else(|| err("missing metric name"))?;
+            let rest = parts.next().unwrap_or_default().trim();
+            let fam = family(&mut families, &mut index, name, default_type);
+            match keyword {
+                "HELP" => fam.help = Some(unescape_help(rest, format)),
+                "UNIT" => fam.unit = Some(rest.to_string()).filter(|u| !u.is_empty()),
+                _ => {
+                    if !types.contains(&rest) {
+                        return Err(err(&format!("unknown metric type \"{}\"", rest)));
+                    }
+                    fam.type_name = rest.to_string();
+                }
+            }
+            continue;
+        }
+
+        let sample = parse_sample(line, format).map_err(|e| err(&e))?;
+        let owner = family_suffixes_owner(&families, &index, &sample.name)
+            .unwrap_or_else(|| sample.name.clone());
+        family(&mut families, &mut index, &owner, default_type)
+            .samples
+            .push(sample);
+    }
+    Ok(families)
+}
+
+/// Name of the histogram or summary family a suffixed sample (`foo_bucket`) belongs to
+fn family_suffixes_owner(
+    families: &[MetricFamily],
+    index: &HashMap<String, usize>,
+    sample_name: &str,
+) -> Option<String> {
+    if index.contains_key(sample_name) {
+        return None;
+    }
+    index.iter().find_map(|(name, &i)| {
+        let suffix = sample_name.strip_prefix(name.as_str())?;
+        family_suffixes(&families[i].type_name)
+            .contains(&suffix)
+            .then(|| name.clone())
+    })
+}
+
+/// Unescape `\\` and `\n` in HELP text (plus `\"` in OpenMetrics)
+fn unescape_help(s: &str, format: Format) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('"')) if format == Format::OpenMetrics => {
+                out.push('"');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                out.push('\\');
+                chars.next();
+            }
+            ('\\', Some('n')) => {
+                out.push('\n');
+                chars.next();
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn is_name_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_' || c == ':'
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == ':'
+}
+
+/// Parse `name{labels} value [timestamp]`. A quoted name inside the braces
+/// (`{"my.metric", job="x"}`) is accepted as well.
+fn parse_sample(line: &str, format: Format) -> Result<Sample, String> {
+    let name_len = line
+        .char_indices()
+        .find(|&(i, c)| !(is_name_char(c) && (i > 0 || is_name_start(c))))
+        .map(|(i, _)| i)
+        .unwrap_or(line.len());
+    let mut name = line[..name_len].to_string();
+    let mut rest = &line[name_len..];
+    let mut labels = HashMap::new();
+
+    if let Some(body) = rest.strip_prefix('{') {
+        rest = body;
+        loop {
+            rest = rest.trim_start();
+            if let Some(r) = rest.strip_prefix('}') {
+                rest = r;
+                break;
+            }
+            let (label, r) = if rest.starts_with('"') {
+                parse_quoted(rest)?
+            } else {
+                let len = rest
+                    .char_indices()
+                    .find(|&(i, c)| !(is_name_char(c) && c != ':' && (i > 0 || is_name_start(c))))
+                    .map(|(i, _)| i)
+                    .unwrap_or(rest.len());
+                if len == 0 {
+                    return Err(format!("invalid label name at \"{}\"", rest));
+                }
+                (rest[..len].to_string(), &rest[len..])
+            };
+            rest = r.trim_start();
+            if let Some(r) = rest.strip_prefix('=') {
+                let (value, r) = parse_quoted(r.trim_start())?;
+                labels.insert(label, value);
+                rest = r;
+            } else if name.is_empty() {
+                name = label;
+            } else {
+                return Err(format!("expected '=' after label \"{}\"", label));
+            }
+            rest = rest.trim_start();
+            if let Some(r) = rest.strip_prefix(',') {
+                rest = r;
+            } else if !rest.starts_with('}') {
+                return Err("expected ',' or '}' in label set".to_string());
+            }
+        }
+    }
+    if name.is_empty() {
+        return Err("missing metric name".to_string());
+    }
+
+    if format == Format::OpenMetrics {
+        // Drop the exemplar: `value [timestamp] # {labels} value [timestamp]`
+        if let Some(i) = rest.find(" # ") {
+            rest = &rest[..i];
+        }
+    }
+    let mut fields = rest.split_whitespace();
+    let raw_value = fields
+        .next()
+        .ok_or_else(|| format!("missing value for \"{}\"", name))?;
+    let value = parse_value(raw_value)?;
+    let timestamp_ms = match fields.next() {
+        Some(ts) => Some(parse_timestamp(ts, format)?),
+        None => None,
+    };
+    if let Some(extra) = fields.next() {
+        return Err(format!("unexpected \"{}\" after sample", extra));
+    }
+
+    Ok(Sample {
+        name,
+        labels,
+        value,
+        timestamp_ms,
+    })
+}
+
+/// Parse a sample timestamp into milliseconds: integer milliseconds in the text format,
+/// seconds with an optional fraction in OpenMetrics
+fn parse_timestamp(s: &str, format: Format) -> Result<i64, String> {
+    let invalid = || format!("invalid timestamp \"{}\"", s);
+    match format {
+        Format::Text => s.parse::<i64>().map_err(|_| invalid()),
+        Format::OpenMetrics => s
+            .parse::<f64>()
+            .ok()
+            .filter(|t| t.is_finite())
+            .map(|t| (t * 1000.0).round() as i64)
+            .ok_or_else(invalid),
+    }
+}
+
+/// Parse a sample value, accepting Go's spellings of infinities (`+Inf`, `-Inf`, `Inf`)
+fn parse_value(s: &str) -> Result<f64, String> {
+    s.parse::<f64>()
+        .map_err(|_| format!("invalid sample value \"{}\"", s))
+}
+
+/// Parse a double-quoted string with `\\`, `\"` and `\n` escapes; returns it and the remainder
+fn parse_quoted(s: &str) -> Result<(String, &str), String> {
+    let body = s
+        .strip_prefix('"')
+        .ok_or_else(|| "expected '\"'".to_string())?;
+    let mut out = String::new();
+    let mut chars = body.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((out, &body[i + 1..])),
+            '\\' => match chars.next() {
+                Some((_, 'n')) => out.push('\n'),
+                Some((_, '"')) => out.push('"'),
+                Some((_, '\\')) => out.push('\\'),
+                Some((_, other)) => {
+                    out.push('\\');
+                    out.push(other);
+                }
+                None => break,
+            },
+            _ => out.push(c),
+        }
+    }
+    Err("unterminated quoted string".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_text_format() {
+        let input = r#"# HELP http_requests_total The total number of HTTP requests.
+# TYPE http_requests_total counter
+http_requests_total{method="post",code="200"} 1027 1395066363000
+http_requests_total{method="post",code="400"}    3 1395066363000
+
+# A plain comment
+msdos_file_access_time_seconds{path="C:\\DIR\\FILE.TXT",error="Cannot find file:\n\"FILE.TXT\""} 1.458255915e9
+# TYPE rpc_duration_seconds histogram
+rpc_duration_seconds_bucket{le="0.05"} 24054
+rpc_duration_seconds_bucket{le="+Inf"} 144320
+rpc_duration_seconds_sum 53423
+rpc_duration_seconds_count 144320
+{"my.dotted.metric", job="x"} NaN
+"#;
+        let families = parse_text_format(input).unwrap();
+        let names: Vec<&str> = families.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "http_requests_total",
+                "msdos_file_access_time_seconds",
+                "rpc_duration_seconds",
+                "my.dotted.metric"
+            ]
+        );
+
+        let requests = &families[0];
+        assert_eq!(requests.type_name, "counter");
+        assert_eq!(
+            requests.help.as_deref(),
+            Some("The total number of HTTP requests.")
+        );
+        assert_eq!(requests.samples.len(), 2);
+        assert_eq!(requests.samples[1].labels["code"], "400");
+        assert_eq!(requests.samples[1].value, 3.0);
+        assert_eq!(requests.samples[1].timestamp_ms, Some(1395066363000));
+
+        let msdos = &families[1].samples[0];
+        assert_eq!(families[1].type_name, "untyped");
+        assert_eq!(msdos.labels["path"], "C:\\DIR\\FILE.TXT");
+        assert_eq!(msdos.labels["error"], "Cannot find file:\n\"FILE.TXT\"");
+        assert_eq!(msdos.timestamp_ms, None);
+
+        let histogram = &families[2];
+        assert_eq!(histogram.samples.len(), 4);
+        assert_eq!(histogram.samples[1].labels["le"], "+Inf");
+        assert_eq!(parse_value("+Inf").unwrap(), f64::INFINITY);
+        assert_eq!(parse_value("-Inf").unwrap(), f64::NEG_INFINITY);
+        assert_eq!(histogram.samples[3].name, "rpc_duration_seconds_count");
+
+        let out = serde_json::to_value(&families[3]).unwrap();
+        assert_eq!(out["samples"][0]["value"], "NaN");
+        assert_eq!(out["samples"][0]["labels"]["job"], "x");
+        assert!(out["samples"][0].get("timestamp_ms").is_none());
+    }
+
+    #[test]
+    fn test_parse_openmetrics() {
+        let input = r#"# TYPE acme_http_router_request_seconds summary
+# UNIT acme_http_router_request_seconds seconds
+# HELP acme_http_router_request_seconds Latency though all of ACME's \"HTTP\" router.
+acme_http_router_request_seconds_sum{path="/api/v1",method="GET"} 9036.32
+acme_http_router_request_seconds_count{path="/api/v1",method="GET"} 807283.0
+acme_http_router_request_seconds_created{path="/api/v1",method="GET"} 1605281325.0
+# TYPE foo counter
+foo_total{a="b"} 17.0 1520879607.789 # {trace_id="KOO5S4vxi0o"} 0.67
+foo_created{a="b"} 1520872607.123
+# TYPE build info
+build_info{version="1.0"} 1
+# EOF
+ignored 1
+"#;
+        let families = parse_openmetrics(input).unwrap();
+        assert_eq!(families.len(), 3);
+
+        let summary = &families[0];
+        assert_eq!(summary.type_name, "summary");
+        assert_eq!(summary.unit.as_deref(), Some("seconds"));
+        assert_eq!(
+            summary.help.as_deref(),
+            Some("Latency though all of ACME's \"HTTP\" router.")
+        );
+        assert_eq!(summary.samples.len(), 3);
+
+        let counter = &families[1];
+        assert_eq!(counter.samples.len(), 2);
+        assert_eq!(counter.samples[0].value, 17.0);
+        assert_eq!(counter.samples[0].timestamp_ms, Some(1520879607789));
+        assert_eq!(families[2].samples[0].name, "build_info");
+
+        // The same exemplar line is invalid in the text format
+        assert!(parse_text_format("foo_total 17.0 # {a=\"b\"} 1").is_err());
+    }
+
+    #[test]
+    fn test_parse_text_format_errors() {
+        assert!(parse_text_format("up{job=\"x\" 1").is_err());
+        assert!(parse_text_format("up{job=\"x} 1").is_err());
+        assert!(parse_text_format("up").is_err());
+        assert!(parse_text_format("up one").is_err());
+        assert!(parse_text_format("up 1 soon").is_err());
+        let err = parse_text_format("up 1\n# TYPE up meter").unwrap_err();
+        assert!(err.starts_with("line 2:"), "{}", err);
+    }
+}
@@ -0,0 +1,259 @@
+use regex::Regex;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Label matcher operator, mirroring PromQL selector syntax
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchOp {
+    /// `=`
+    Equal,
+    /// `!=`
+    NotEqual,
+    /// `=~`
+    RegexMatch,
+    /// `!~`
+    RegexNoMatch,
+}
+
+impl fmt::Display for MatchOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = match self {
+            MatchOp::Equal => "=",
+            MatchOp::NotEqual => "!=",
+            MatchOp::RegexMatch => "=~",
+            MatchOp::RegexNoMatch => "!~",
+        };
+        write!(f, "{}", op)
+    }
+}
+
+/// A single label matcher such as `severity="critical"` or `job=~"node.*"`
+#[derive(Debug, Clone)]
+pub struct LabelMatcher {
+    pub name: String,
+    pub op: MatchOp,
+    pub value: String,
+    regex: Option<Regex>,
+}
+
+impl LabelMatcher {
+    /// Build a matcher, compiling the (fully anchored) regex for regex operators
+    pub fn new(name: &str, op: MatchOp, value: &str) -> Result<Self, String> {
+        let regex = match op {
+            MatchOp::RegexMatch | MatchOp::RegexNoMatch => Some(
+                Regex::new(&format!("^(?:{})$", value))
+                    .map_err(|e| format!("invalid regex in matcher for '{}': {}", name, e))?,
+            ),
+            _ => None,
+        };
+        Ok(Self {
+            name: name.to_string(),
+            op,
+            value: value.to_string(),
+            regex,
+        })
+    }
+
+    /// Check the matcher against a label set; a missing label is treated as empty
+    pub fn matches(&self, labels: &HashMap<String, String>) -> bool {
+        let value = labels.get(&self.name).map(String::as_str).unwrap_or("");
+        match self.op {
+            MatchOp::Equal => value == self.value,
+            MatchOp::NotEqual => value != self.value,
+            MatchOp::RegexMatch => self.regex.as_ref().is_some_and(|r| r.is_match(value)),
+            MatchOp::RegexNoMatch => !self.regex.as_ref().is_some_and(|r| r.is_match(value)),
+        }
+    }
+}
+
+impl fmt::Display for LabelMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}{:?}", self.name, self.op, self.value)
+    }
+}
+
+/// Parse one or more comma-separated matchers, optionally wrapped in `{}`.
+///
+/// Accepts `severity="critical"`, `{team=~"db.*", env!="dev"}` and similar.
+pub fn parse_matchers(input: &str) -> Result<Vec<LabelMatcher>, String> {
+    let mut s = input.trim();
+    if let Some(inner) = s.strip_prefix('{').and_then(|r| r.strip_suffix('}')) {
+        s = inner.trim();
+    }
+
+    let chars: Vec<char> = s.chars().collect();
+    let mut pos = 0;
+    let mut out = Vec::new();
+
+    while pos < chars.len() {
+        skip_whitespace(&chars, &mut pos);
+        if pos >= chars.len() {
+            break;
+        }
+
+        let name_start = pos;
+        while pos < chars.len() && (chars[pos].is_ascii_alphanumeric() || chars[pos] == '_') {
+            pos += 1;
+        }
+        let name: String = chars[name_start..pos].iter().collect();
+        if name.is_empty() {
+            return Err(format!(
+                "expected label name at position {} in '{}'",
+                pos, input
+            ));
+        }
+
+        skip_whitespace(&chars, &mut pos);
+        let op = match (chars.get(pos), chars.get(pos + 1)) {
+            (Some('='), Some('~')) => MatchOp::RegexMatch,
+            (Some('!'), Some('~')) => MatchOp::RegexNoMatch,
+            (Some('!'), Some('=')) => MatchOp::NotEqual,
+            (Some('='), _) => MatchOp::Equal,
+            _ => {
+                return Err(format!(
+                    "expected matcher operator after '{}' in '{}'",
+                    name, input
+                ))
+            }
+        };
+        pos += if op == MatchOp::Equal { 1 } else { 2 };
+
+        skip_whitespace(&chars, &mut pos);
+        let value = parse_quoted(&chars, &mut pos)
+            .ok_or_else(|| format!("expected quoted value for '{}' in '{}'", name, input))?;
+        out.push(LabelMatcher::new(&name, op, &value)?);
+
+        skip_whitespace(&chars, &mut pos);
+        match chars.get(pos) {
+            Some(',') => pos += 1,
+            None => {}
+            Some(c) => return Err(format!("unexpected '{}' in '{}'", c, input)),
+        }
+    }
+
+    Ok(out)
+}
+
+/// Parse a series selector such as `up{job="node"}` or `{__name__=~"node_.*"}`.
+///
+/// A leading metric name becomes a `__name__` equality matcher.
+pub fn parse_selector(input: &str) -> Result<Vec<LabelMatcher>, String> {
+    let s = input.trim();
+    let (name, rest) = match s.find('{') {
+        Some(i) => (s[..i].trim(), &s[i..]),
+        None => (s, ""),
+    };
+
+    let mut out = Vec::new();
+    if !name.is_empty() {
+        if !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+        {
+            return Err(format!("invalid metric name '{}' in '{}'", name, input));
+        }
+        out.push(LabelMatcher::new("__name__", MatchOp::Equal, name)?);
+    }
+    if !rest.is_empty() {
+        if !rest.ends_with('}') {
+            return Err(format!("unterminated '{{' in '{}'", input));
+        }
+        out.extend(parse_matchers(rest)?);
+    }
+    if out.is_empty() {
+        return Err(format!(
+            "selector '{}' needs a metric name or at least one matcher",
+            input
+        ));
+    }
+    Ok(out)
+}
+
+/// Check that every matcher matches the label set
+pub fn matches_all(matchers: &[LabelMatcher], labels: &HashMap<String, String>) -> bool {
+    matchers.iter().all(|m| m.matches(labels))
+}
+
+fn skip_whitespace(chars: &[char], pos: &mut usize) {
+    while *pos < chars.len() && chars[*pos].is_whitespace() {
+        *pos += 1;
+    }
+}
+
+fn parse_quoted(chars: &[char], pos: &mut usize) -> Option<String> {
+    let quote = *chars.get(*pos)?;
+    if quote != '"' && quote != '\'' && quote != '`' {
+        return None;
+    }
+    *pos += 1;
+    let mut out = String::new();
+    while let Some(&c) = chars.get(*pos) {
+        *pos += 1;
+        if c == quote {
+            return Some(out);
+        }
+        if c == '\\' && quote != '`' {
+            let escaped = *chars.get(*pos)?;
+            *pos += 1;
+            match escaped {
+                'n' => out.push('\n'),
+                't' => out.push('\t'),
+                other => out.push(other),
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_and_match() {
+        let ms = parse_matchers(r#"{severity="critical", team=~"db.*", env!="dev"}"#).unwrap();
+        assert_eq!(ms.len(), 3);
+        assert_eq!(ms[1].op, MatchOp::RegexMatch);
+
+        let l = labels(&[("severity", "critical"), ("team", "dba"), ("env", "prod")]);
+        assert!(matches_all(&ms, &l));
+
+        let l = labels(&[("severity", "critical"), ("team", "xdb"), ("env", "prod")]);
+        assert!(!matches_all(&ms, &l), "regex must be anchored");
+
+        let ms = parse_matchers(r#"instance!~"localhost:.*""#).unwrap();
+        assert!(ms[0].matches(&labels(&[])));
+    }
+
+    #[test]
+    fn test_parse_selector() {
+        let ms = parse_selector(r#"node_cpu_seconds_total{mode="idle", cpu=~"0|1"}"#).unwrap();
+        assert_eq!(ms.len(), 3);
+        assert_eq!(ms[0].name, "__name__");
+        assert_eq!(ms[0].value, "node_cpu_seconds_total");
+
+        let ms = parse_selector(r#"{__name__=~"up|scrape_.*"}"#).unwrap();
+        assert_eq!(ms.len(), 1);
+        assert_eq!(ms[0].op, MatchOp::RegexMatch);
+
+        assert!(parse_selector("up").is_ok());
+        assert!(parse_selector("{}").is_err());
+        assert!(parse_selector(r#"up{job="x""#).is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse_matchers("severity").is_err());
+        assert!(parse_matchers(r#"severity="critical"#).is_err());
+        assert!(parse_matchers(r#"job=~"(""#).is_err());
+    }
+}
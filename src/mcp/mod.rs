@@ -1,9 +1,22 @@
+pub mod alertmanager_client;
+pub mod alertmanager_config;
+pub mod auth;
 pub mod compat;
 pub mod exporter;
+pub mod exposition;
+pub mod http_client;
+pub mod matchers;
 pub mod metrics;
 pub mod prometheus_client;
 pub mod prometheus_config;
+pub mod redact;
+pub mod remote_read;
 pub mod repository;
+pub mod scrape;
+pub mod tenant;
+#[cfg(test)]
+pub mod test_server;
+pub mod time;
 pub mod tools;
 pub mod types;
 pub mod utilities;
@@ -1,15 +1,24 @@
+use crate::mcp::auth::Authorization;
+use crate::mcp::exposition::{parse_text_format, MetricFamily};
+use crate::mcp::http_client::{self, RequestHeaders};
 use crate::mcp::prometheus_config::PrometheusConfig;
-use reqwest::{Client, Error as ReqwestError, RequestBuilder};
+use crate::mcp::tenant;
+use reqwest::{Client, Error as ReqwestError, Method, RequestBuilder, StatusCode};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// Prometheus API client
 pub struct PrometheusClient {
     pub(crate) config: PrometheusConfig,
     client: Client,
+    auth: Authorization,
+    headers: RequestHeaders,
+    /// Set once the backend answered a POST with 405; later requests stay on GET
+    post_rejected: AtomicBool,
 }
 
 /// Prometheus query result
@@ -17,14 +26,147 @@ pub struct PrometheusClient {
 pub struct PrometheusQueryResult {
     pub status: String,
     pub data: PrometheusData,
+    /// Warnings about the query or its result (e.g. partial data)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// Informational PromQL annotations (e.g. `rate()` applied to a gauge)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub infos: Vec<String>,
 }
 
 /// Prometheus data
 #[derive(Debug, Deserialize, Serialize)]
 pub struct PrometheusData {
-    #[serde(rename = "resultType")]
-    pub result_type: String,
-    pub result: Vec<PrometheusResult>,
+    #[serde(flatten)]
+    pub result: PrometheusResultData,
+    /// Query statistics, present when requested with `stats`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stats: Option<QueryStats>,
+}
+
+/// Per-query options for instant and range queries
+#[derive(Debug, Clone, Default)]
+pub struct QueryOptions {
+    /// Ask Prometheus for query statistics (`stats=all`)
+    pub stats: bool,
+    /// Server-side evaluation timeout, e.g. `30s`
+    pub timeout: Option<String>,
+    /// Maximum number of returned series (Prometheus 3.x)
+    pub limit: Option<u64>,
+    /// Thanos: merge replicas by the replica labels
+    pub dedup: Option<bool>,
+    /// Thanos: return what is available when some stores fail, with warnings
+    pub partial_response: Option<bool>,
+    /// Thanos: coarsest downsampling resolution to use, e.g. `5m`, `1h`, `0s` (raw) or `auto`
+    pub max_source_resolution: Option<String>,
+    /// Thanos: query engine, `prometheus` or `thanos`
+    pub engine: Option<String>,
+}
+
+impl QueryOptions {
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if self.stats {
+            params.push(("stats", "all".to_string()));
+        }
+        if let Some(t) = &self.timeout {
+            params.push(("timeout", t.clone()));
+        }
+        if let Some(l) = self.limit {
+            params.push(("limit", l.to_string()));
+        }
+        if let Some(d) = self.dedup {
+            params.push(("dedup", d.to_string()));
+        }
+        if let Some(p) = self.partial_response {
+            params.push(("partial_response", p.to_string()));
+        }
+        if let Some(r) = &self.max_source_resolution {
+            params.push(("max_source_resolution", r.clone()));
+        }
+        if let Some(e) = &self.engine {
+            params.push(("engine", e.clone()));
+        }
+        params
+    }
+
+    /// Names of the Thanos-only options that are set
+    pub fn thanos_options_set(&self) -> Vec<&'static str> {
+        [
+            ("dedup", self.dedup.is_some()),
+            ("partial_response", self.partial_response.is_some()),
+            (
+                "max_source_resolution",
+                self.max_source_resolution.is_some(),
+            ),
+            ("engine", self.engine.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(name, set)| set.then_some(name))
+        .collect()
+    }
+}
+
+/// Query statistics returned with `stats=all`; timings are in seconds
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct QueryStats {
+    #[serde(default)]
+    pub timings: QueryTimings,
+    #[serde(default)]
+    pub samples: QuerySamples,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QueryTimings {
+    pub eval_total_time: f64,
+    pub result_sort_time: f64,
+    pub query_preparation_time: f64,
+    pub inner_eval_time: f64,
+    pub exec_queue_time: f64,
+    pub exec_total_time: f64,
+}
+
+/// Sample counts; the per-step breakdown is dropped to keep results small
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QuerySamples {
+    pub total_queryable_samples: u64,
+    pub peak_samples: u64,
+}
+
+/// Query result keyed by `resultType`
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(tag = "resultType", content = "result", rename_all = "lowercase")]
+pub enum PrometheusResultData {
+    /// Instant vector: one sample per series
+    Vector(Vec<PrometheusResult>),
+    /// Range vector: a list of samples per series
+    Matrix(Vec<PrometheusResult>),
+    /// Single numeric sample, e.g. `scalar(sum(up))` or `time()`
+    Scalar((f64, String)),
+    /// Single string sample
+    String((f64, String)),
+}
+
+impl PrometheusResultData {
+    /// The `resultType` as reported by Prometheus
+    pub fn result_type(&self) -> &'static str {
+        match self {
+            PrometheusResultData::Vector(_) => "vector",
+            PrometheusResultData::Matrix(_) => "matrix",
+            PrometheusResultData::Scalar(_) => "scalar",
+            PrometheusResultData::String(_) => "string",
+        }
+    }
+
+    /// Series of a vector or matrix result; empty for scalars and strings
+    pub fn series(&self) -> &[PrometheusResult] {
+        match self {
+            PrometheusResultData::Vector(series) | PrometheusResultData::Matrix(series) => series,
+            PrometheusResultData::Scalar(_) | PrometheusResultData::String(_) => &[],
+        }
+    }
 }
 
 /// Prometheus result
@@ -35,18 +177,303 @@ pub struct PrometheusResult {
     pub value: Option<(f64, String)>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub values: Option<Vec<(f64, String)>>,
+    /// Native histogram sample of an instant vector
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub histogram: Option<(f64, NativeHistogram)>,
+    /// Native histogram samples of a range vector
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub histograms: Option<Vec<(f64, NativeHistogram)>>,
 }
 
-/// Metadata about a metric
-#[derive(Debug, Deserialize, Serialize)]
+/// Native histogram sample; numbers are kept as strings like float sample values
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NativeHistogram {
+    pub count: String,
+    pub sum: String,
+    #[serde(default)]
+    pub buckets: Vec<HistogramBucket>,
+}
+
+/// Native histogram bucket.
+///
+/// Prometheus encodes buckets as `[boundary_rule, lower, upper, count]`; the rule is
+/// expanded into explicit inclusiveness flags for each boundary.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(from = "(u8, String, String, String)")]
+pub struct HistogramBucket {
+    pub lower: String,
+    pub upper: String,
+    pub count: String,
+    pub lower_inclusive: bool,
+    pub upper_inclusive: bool,
+}
+
+impl From<(u8, String, String, String)> for HistogramBucket {
+    fn from((rule, lower, upper, count): (u8, String, String, String)) -> Self {
+        // 0: (lower, upper], 1: [lower, upper), 2: (lower, upper), 3: [lower, upper]
+        let (lower_inclusive, upper_inclusive) = match rule {
+            0 => (false, true),
+            1 => (true, false),
+            2 => (false, false),
+            _ => (true, true),
+        };
+        Self {
+            lower,
+            upper,
+            count,
+            lower_inclusive,
+            upper_inclusive,
+        }
+    }
+}
+
+/// Metadata about a metric; fields missing from the response are left empty
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct MetricMetadata {
+    #[serde(default)]
     pub metric: String,
-    #[serde(rename = "type")]
+    #[serde(rename = "type", default)]
     pub type_name: String,
+    #[serde(default)]
     pub help: String,
+    #[serde(default)]
     pub unit: String,
 }
 
+/// Metric metadata as exposed by a single target (`/api/v1/targets/metadata`)
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct TargetMetadata {
+    #[serde(default)]
+    pub target: HashMap<String, String>,
+    #[serde(default)]
+    pub metric: String,
+    #[serde(rename = "type", default)]
+    pub type_name: String,
+    #[serde(default)]
+    pub help: String,
+    #[serde(default)]
+    pub unit: String,
+}
+
+/// Raw samples of one series for a time range
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct RawSeries {
+    pub labels: HashMap<String, String>,
+    /// `[timestamp_ms, value]` pairs in time order; values are formatted like the HTTP API
+    pub samples: Vec<(i64, String)>,
+}
+
+/// Format a sample value the way the HTTP API does (`NaN`, `+Inf`, `-Inf`, plain decimals)
+pub fn format_sample_value(v: f64) -> String {
+    if v.is_nan() {
+        "NaN".to_string()
+    } else if v.is_infinite() {
+        if v > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        v.to_string()
+    }
+}
+
+/// Series returned by `/api/v1/series`
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct SeriesResult {
+    pub series: Vec<HashMap<String, String>>,
+    /// Whether `limit` cut the result short
+    pub truncated: bool,
+}
+
+/// Active alert as returned by `/api/v1/alerts`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Alert {
+    pub labels: HashMap<String, String>,
+    #[serde(default)]
+    pub annotations: HashMap<String, String>,
+    pub state: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+/// Rule group as returned by `/api/v1/rules`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleGroup {
+    pub name: String,
+    pub file: String,
+    pub rules: Vec<Rule>,
+    /// Evaluation interval in seconds
+    #[serde(default)]
+    pub interval: f64,
+    #[serde(default)]
+    pub evaluation_time: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_evaluation: Option<String>,
+}
+
+/// Alerting or recording rule
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Rule {
+    Alerting(AlertingRule),
+    Recording(RecordingRule),
+}
+
+/// Alerting rule with its current alerts
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertingRule {
+    pub name: String,
+    pub query: String,
+    /// `for` duration in seconds
+    #[serde(default)]
+    pub duration: f64,
+    #[serde(default)]
+    pub keep_firing_for: f64,
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    #[serde(default)]
+    pub annotations: HashMap<String, String>,
+    #[serde(default)]
+    pub alerts: Vec<Alert>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    pub health: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub evaluation_time: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_evaluation: Option<String>,
+}
+
+/// Recording rule
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingRule {
+    pub name: String,
+    pub query: String,
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    pub health: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub evaluation_time: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_evaluation: Option<String>,
+}
+
+/// TSDB cardinality statistics as returned by `/api/v1/status/tsdb`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TsdbStatus {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head_stats: Option<HeadStats>,
+    #[serde(default)]
+    pub series_count_by_metric_name: Vec<TsdbStat>,
+    #[serde(default)]
+    pub label_value_count_by_label_name: Vec<TsdbStat>,
+    #[serde(default)]
+    pub memory_in_bytes_by_label_name: Vec<TsdbStat>,
+    #[serde(default)]
+    pub series_count_by_label_value_pair: Vec<TsdbStat>,
+}
+
+/// Head block statistics
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeadStats {
+    pub num_series: u64,
+    #[serde(default)]
+    pub num_label_pairs: u64,
+    pub chunk_count: u64,
+    /// Milliseconds since epoch
+    pub min_time: i64,
+    /// Milliseconds since epoch
+    pub max_time: i64,
+}
+
+/// Name/value entry of a TSDB top-N list
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TsdbStat {
+    pub name: String,
+    pub value: u64,
+}
+
+/// Exemplars for one series as returned by `/api/v1/query_exemplars`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExemplarSeries {
+    pub series_labels: HashMap<String, String>,
+    #[serde(default)]
+    pub exemplars: Vec<Exemplar>,
+}
+
+/// A single exemplar
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Exemplar {
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    pub value: String,
+    pub timestamp: f64,
+    /// Trace ID taken from the exemplar labels (`trace_id`, `traceID`, ...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+}
+
+/// Exemplar label names commonly used for trace IDs
+const TRACE_ID_LABELS: &[&str] = &["trace_id", "traceID", "traceId", "TraceID", "trace-id"];
+
+impl Exemplar {
+    /// Find the trace ID among the exemplar labels
+    pub fn trace_id_label(&self) -> Option<&str> {
+        TRACE_ID_LABELS
+            .iter()
+            .find_map(|k| self.labels.get(*k))
+            .map(String::as_str)
+    }
+}
+
+/// Section of the `/api/v1/status/*` endpoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusSection {
+    BuildInfo,
+    RuntimeInfo,
+    Flags,
+    Config,
+}
+
+impl StatusSection {
+    /// Path segment below `/api/v1/status/`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusSection::BuildInfo => "buildinfo",
+            StatusSection::RuntimeInfo => "runtimeinfo",
+            StatusSection::Flags => "flags",
+            StatusSection::Config => "config",
+        }
+    }
+}
+
+impl std::str::FromStr for StatusSection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "buildinfo" => Ok(StatusSection::BuildInfo),
+            "runtimeinfo" => Ok(StatusSection::RuntimeInfo),
+            "flags" => Ok(StatusSection::Flags),
+            "config" => Ok(StatusSection::Config),
+            other => Err(format!(
+                "unknown status section '{}' (expected buildinfo, runtimeinfo, flags or config)",
+                other
+            )),
+        }
+    }
+}
+
 /// Prometheus API error
 #[derive(Debug)]
 #[allow(clippy::enum_variant_names)]
@@ -59,6 +486,50 @@ pub enum PrometheusError {
     ParseError(String),
     /// Error building HTTP client
     BuildClientError(String),
+    /// Endpoint not available on this backend (e.g. older Prometheus or a proxy)
+    Unsupported(String),
+    /// Invalid parameters or PromQL (`bad_data`)
+    BadData(String),
+    /// Query exceeded the server-side timeout (`timeout`)
+    Timeout(String),
+    /// Query was canceled by the server (`canceled`)
+    Canceled(String),
+    /// Query failed during evaluation, e.g. too many samples (`execution`)
+    Execution(String),
+    /// Server is not ready or a dependency is down (`unavailable`)
+    Unavailable(String),
+    /// Requested resource does not exist (`not_found`)
+    NotFound(String),
+    /// Unexpected server-side failure (`internal`)
+    Internal(String),
+}
+
+impl PrometheusError {
+    /// Map an API `errorType` and `error` message to a typed error
+    pub fn from_api_error(error_type: &str, error: &str) -> Self {
+        let msg = error.to_string();
+        match error_type {
+            "bad_data" => PrometheusError::BadData(msg),
+            "timeout" => PrometheusError::Timeout(msg),
+            "canceled" => PrometheusError::Canceled(msg),
+            "execution" => PrometheusError::Execution(msg),
+            "unavailable" => PrometheusError::Unavailable(msg),
+            "not_found" => PrometheusError::NotFound(msg),
+            "internal" => PrometheusError::Internal(msg),
+            other => PrometheusError::ApiError(format!("{}: {}", other, error)),
+        }
+    }
+
+    /// Whether retrying the same request could succeed
+    pub fn is_retryable(&self) -> bool {
+        !matches!(
+            self,
+            PrometheusError::BadData(_)
+                | PrometheusError::Execution(_)
+                | PrometheusError::NotFound(_)
+                | PrometheusError::Unsupported(_)
+        )
+    }
 }
 
 impl fmt::Display for PrometheusError {
@@ -68,6 +539,14 @@ impl fmt::Display for PrometheusError {
             PrometheusError::ApiError(msg) => write!(f, "Prometheus API error: {}", msg),
             PrometheusError::ParseError(msg) => write!(f, "Parse error: {}", msg),
             PrometheusError::BuildClientError(msg) => write!(f, "Client build error: {}", msg),
+            PrometheusError::Unsupported(msg) => write!(f, "Unsupported endpoint: {}", msg),
+            PrometheusError::BadData(msg) => write!(f, "Bad data: {}", msg),
+            PrometheusError::Timeout(msg) => write!(f, "Query timed out: {}", msg),
+            PrometheusError::Canceled(msg) => write!(f, "Query canceled: {}", msg),
+            PrometheusError::Execution(msg) => write!(f, "Query execution failed: {}", msg),
+            PrometheusError::Unavailable(msg) => write!(f, "Prometheus unavailable: {}", msg),
+            PrometheusError::NotFound(msg) => write!(f, "Not found: {}", msg),
+            PrometheusError::Internal(msg) => write!(f, "Internal Prometheus error: {}", msg),
         }
     }
 }
@@ -82,26 +561,33 @@ impl From<ReqwestError> for PrometheusError {
 
 impl PrometheusClient {
     /// Create a new PrometheusClient with the given configuration
-    pub fn new(config: PrometheusConfig) -> Result<Self, PrometheusError> {
-        let builder = Client::builder().timeout(config.timeout);
-        let client = builder
-            .build()
-            .map_err(|e| PrometheusError::BuildClientError(e.to_string()))?;
+    pub fn new(mut config: PrometheusConfig) -> Result<Self, PrometheusError> {
+        let (client, url) = http_client::build_client(&config, &config.url)?;
+        config.url = url;
+        let auth = Authorization::from_config(&config)?;
+        let headers = RequestHeaders::from_config(&config)?;
 
-        Ok(Self { config, client })
+        Ok(Self {
+            config,
+            client,
+            auth,
+            headers,
+            post_rejected: AtomicBool::new(false),
+        })
     }
 
-    /// Apply basic auth if configured
     fn build_get(&self, url: &str) -> RequestBuilder {
-        let rb = self.client.get(url);
-        match (&self.config.username, &self.config.password) {
-            (Some(user), Some(pass)) => rb.basic_auth(user, Some(pass)),
-            _ => rb,
-        }
+        self.build_request(Method::GET, url)
     }
 
-    /// Internal helper: optionally rate-limit, send request, and error-check HTTP status.
-    async fn send_request_response(
+    /// Apply static headers, authorization and the tenant header if configured
+    fn build_request(&self, method: Method, url: &str) -> RequestBuilder {
+        let rb = self.headers.apply(self.client.request(method, url));
+        tenant::apply_tenant(self.auth.apply(rb), &self.config)
+    }
+
+    /// Internal helper: optionally rate-limit and send a request without checking the status.
+    async fn send_raw(
         &self,
         rb: RequestBuilder,
         rate_limit: bool,
@@ -111,17 +597,63 @@ impl PrometheusClient {
                 tokio::time::sleep(Duration::from_millis(min_interval)).await;
             }
         }
-        let response = rb.send().await?;
+        Ok(rb.send().await?)
+    }
+
+    /// Internal helper: optionally rate-limit, send request, and error-check HTTP status.
+    async fn send_request_response(
+        &self,
+        rb: RequestBuilder,
+        rate_limit: bool,
+    ) -> Result<reqwest::Response, PrometheusError> {
+        let response = self.send_raw(rb, rate_limit).await?;
+        Self::check_status(response).await
+    }
+
+    /// Send a request to a query-style endpoint (query, query_range, series, labels).
+    ///
+    /// Parameters go in the URL unless their encoded size exceeds
+    /// `post_threshold_bytes` or `always_post` is set, in which case they are sent as a
+    /// form-encoded POST body to avoid proxy URL length limits (HTTP 414). Backends
+    /// that reject POST with 405 are retried with GET and stay on GET afterwards.
+    async fn send_query_request(
+        &self,
+        url: &str,
+        params: &[(&str, &str)],
+        rate_limit: bool,
+    ) -> Result<reqwest::Response, PrometheusError> {
+        if self.should_post(params) {
+            let rb = self.build_request(Method::POST, url).form(params);
+            let response = self.send_raw(rb, rate_limit).await?;
+            if response.status() != StatusCode::METHOD_NOT_ALLOWED {
+                return Self::check_status(response).await;
+            }
+            self.post_rejected.store(true, Ordering::Relaxed);
+        }
+        let rb = self.build_get(url).query(params);
+        self.send_request_response(rb, rate_limit).await
+    }
+
+    /// Whether a request with these parameters should be sent as POST
+    fn should_post(&self, params: &[(&str, &str)]) -> bool {
+        if self.post_rejected.load(Ordering::Relaxed) {
+            return false;
+        }
+        self.config.always_post || encoded_len(params) > self.config.post_threshold_bytes
+    }
+
+    /// Turn a non-success response into a typed error from its `errorType`, or an
+    /// `ApiError` carrying the status and body when the body is not an API error
+    async fn check_status(
+        response: reqwest::Response,
+    ) -> Result<reqwest::Response, PrometheusError> {
         if !response.status().is_success() {
             let status = response.status();
             let text = response
                 .text()
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(PrometheusError::ApiError(format!(
-                "Prometheus API error: {} - {}",
-                status, text
-            )));
+            return Err(parse_error_body(status, &text));
         }
         Ok(response)
     }
@@ -131,17 +663,16 @@ impl PrometheusClient {
         &self,
         query: &str,
         time: Option<&str>,
+        options: &QueryOptions,
     ) -> Result<PrometheusQueryResult, PrometheusError> {
         let url = format!("{}/api/v1/query", self.config.url);
-        let mut params: Vec<(&str, &str)> = vec![("query", query)];
-
-        let time_holder;
+        let mut params: Vec<(&str, String)> = vec![("query", query.to_string())];
         if let Some(t) = time {
-            time_holder = t.to_string();
-            params.push(("time", &time_holder));
+            params.push(("time", t.to_string()));
         }
+        params.extend(options.params());
 
-        self.execute_with_retry(url, params).await
+        self.execute_with_retry(url, as_pairs(&params)).await
     }
 
     /// Execute a range query
@@ -151,16 +682,18 @@ impl PrometheusClient {
         start: &str,
         end: &str,
         step: &str,
+        options: &QueryOptions,
     ) -> Result<PrometheusQueryResult, PrometheusError> {
         let url = format!("{}/api/v1/query_range", self.config.url);
-        let params = vec![
-            ("query", query),
-            ("start", start),
-            ("end", end),
-            ("step", step),
+        let mut params: Vec<(&str, String)> = vec![
+            ("query", query.to_string()),
+            ("start", start.to_string()),
+            ("end", end.to_string()),
+            ("step", step.to_string()),
         ];
+        params.extend(options.params());
 
-        self.execute_with_retry(url, params).await
+        self.execute_with_retry(url, as_pairs(&params)).await
     }
 
     /// Execute a query with retry
@@ -174,6 +707,7 @@ impl PrometheusClient {
         for _ in 0..self.config.retries {
             match self.execute_query(&url, &params).await {
                 Ok(result) => return Ok(result),
+                Err(err) if !err.is_retryable() => return Err(err),
                 Err(err) => {
                     last_error = Some(err);
                     // Wait a bit before retrying
@@ -193,8 +727,7 @@ impl PrometheusClient {
         params: &[(&'a str, &'a str)],
     ) -> Result<PrometheusQueryResult, PrometheusError> {
         // Use rate limiting for query endpoints (preserve prior behavior)
-        let rb = self.build_get(url).query(params);
-        let response = self.send_request_response(rb, true).await?;
+        let response = self.send_query_request(url, params, true).await?;
 
         let result: PrometheusQueryResult = response.json().await.map_err(|e| {
             PrometheusError::ParseError(format!("Failed to parse Prometheus response: {}", e))
@@ -203,6 +736,42 @@ impl PrometheusClient {
         Ok(result)
     }
 
+    /// Read raw samples for `selector` in `(start_ms, end_ms]` through the query API, by
+    /// evaluating the range selector `selector[<end - start>ms]` at `end_ms`
+    pub async fn read_raw(
+        &self,
+        selector: &str,
+        start_ms: i64,
+        end_ms: i64,
+    ) -> Result<Vec<RawSeries>, PrometheusError> {
+        if end_ms <= start_ms {
+            return Err(PrometheusError::BadData(
+                "end must be after start".to_string(),
+            ));
+        }
+        let query = format!("{}[{}ms]", selector, end_ms - start_ms);
+        let time = format!("{:.3}", end_ms as f64 / 1000.0);
+        let result = self
+            .query(&query, Some(&time), &QueryOptions::default())
+            .await?;
+
+        Ok(result
+            .data
+            .result
+            .series()
+            .iter()
+            .map(|s| RawSeries {
+                labels: s.metric.clone(),
+                samples: s
+                    .values
+                    .iter()
+                    .flatten()
+                    .map(|(t, v)| ((t * 1000.0).round() as i64, v.clone()))
+                    .collect(),
+            })
+            .collect())
+    }
+
     /// Convert a timestamp to a Prometheus-compatible time string
     #[allow(dead_code)]
     pub fn timestamp_to_prometheus_time(timestamp: SystemTime) -> String {
@@ -241,10 +810,27 @@ impl PrometheusClient {
         Ok(out)
     }
 
-    /// Get metadata about a specific metric
-    pub async fn get_metadata(&self, metric: &str) -> Result<Vec<MetricMetadata>, PrometheusError> {
+    /// Get metric metadata, for a single metric or for all metrics when `metric` is `None`.
+    ///
+    /// `limit` caps the number of metrics returned and `limit_per_metric` the number of
+    /// metadata entries per metric.
+    pub async fn get_metadata(
+        &self,
+        metric: Option<&str>,
+        limit: Option<u64>,
+        limit_per_metric: Option<u64>,
+    ) -> Result<Vec<MetricMetadata>, PrometheusError> {
         let url = format!("{}/api/v1/metadata", self.config.url);
-        let params = vec![("metric", metric)];
+        let mut params: Vec<(&str, String)> = Vec::new();
+        if let Some(m) = metric {
+            params.push(("metric", m.to_string()));
+        }
+        if let Some(l) = limit {
+            params.push(("limit", l.to_string()));
+        }
+        if let Some(l) = limit_per_metric {
+            params.push(("limit_per_metric", l.to_string()));
+        }
 
         let rb = self.build_get(&url).query(&params);
         // No rate limiting here previously; preserve behavior
@@ -255,23 +841,21 @@ impl PrometheusClient {
         })?;
 
         let mut metadata = Vec::new();
-        if let Some(data) = result.get("data") {
-            if let Some(metric_data) = data.get(metric) {
-                if let Some(meta_array) = metric_data.as_array() {
-                    for meta in meta_array {
-                        if let (Some(type_val), Some(help), Some(unit)) = (
-                            meta.get("type").and_then(|v| v.as_str()),
-                            meta.get("help").and_then(|v| v.as_str()),
-                            meta.get("unit").and_then(|v| v.as_str()),
-                        ) {
-                            metadata.push(MetricMetadata {
-                                metric: metric.to_string(),
-                                type_name: type_val.to_string(),
-                                help: help.to_string(),
-                                unit: unit.to_string(),
-                            });
-                        }
-                    }
+        if let Some(data) = result.get("data").and_then(|d| d.as_object()) {
+            for (name, entries) in data {
+                for meta in entries.as_array().into_iter().flatten() {
+                    let field = |key: &str| {
+                        meta.get(key)
+                            .and_then(|v| v.as_str())
+                            .unwrap_or_default()
+                            .to_string()
+                    };
+                    metadata.push(MetricMetadata {
+                        metric: name.clone(),
+                        type_name: field("type"),
+                        help: field("help"),
+                        unit: field("unit"),
+                    });
                 }
             }
         }
@@ -279,22 +863,57 @@ impl PrometheusClient {
         Ok(metadata)
     }
 
-    /// Get time series data for a specific metric with optional label matchers
+    /// Get metric metadata per scrape target, optionally filtered by a target label
+    /// selector (`match_target`) and metric name
+    pub async fn get_targets_metadata(
+        &self,
+        match_target: Option<&str>,
+        metric: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<Vec<TargetMetadata>, PrometheusError> {
+        let url = format!("{}/api/v1/targets/metadata", self.config.url);
+        let mut params: Vec<(&str, String)> = Vec::new();
+        if let Some(m) = match_target {
+            params.push(("match_target", m.to_string()));
+        }
+        if let Some(m) = metric {
+            params.push(("metric", m.to_string()));
+        }
+        if let Some(l) = limit {
+            params.push(("limit", l.to_string()));
+        }
+
+        let rb = self.build_get(&url).query(&params);
+        let response = self.send_request_response(rb, false).await?;
+
+        let result: Value = response.json().await.map_err(|e| {
+            PrometheusError::ParseError(format!("Failed to parse Prometheus response: {}", e))
+        })?;
+
+        match result.get("data") {
+            Some(data) => serde_json::from_value(data.clone()).map_err(|e| {
+                PrometheusError::ParseError(format!("Failed to parse targets metadata: {}", e))
+            }),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Get time series data for a specific metric with optional label matchers,
+    /// optionally bounded to `[start, end]` and capped at `limit` series
     pub async fn get_series(
         &self,
         match_strings: Vec<&str>,
-    ) -> Result<Vec<HashMap<String, String>>, PrometheusError> {
+        start: Option<&str>,
+        end: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<SeriesResult, PrometheusError> {
         let url = format!("{}/api/v1/series", self.config.url);
+        let params = selection_params(match_strings, start, end, limit);
 
-        // Build the match[] parameters
-        let mut params = Vec::new();
-        for m in match_strings {
-            params.push(("match[]", m));
-        }
-
-        let rb = self.build_get(&url).query(&params);
         // No rate limiting here previously; preserve behavior
-        let response = self.send_request_response(rb, false).await?;
+        let response = self
+            .send_query_request(&url, &as_pairs(&params), false)
+            .await?;
 
         let result: Value = response.json().await.map_err(|e| {
             PrometheusError::ParseError(format!("Failed to parse Prometheus response: {}", e))
@@ -317,14 +936,40 @@ impl PrometheusClient {
             }
         }
 
-        Ok(series)
+        // Newer Prometheus versions apply the limit and warn about it; older ones
+        // ignore the parameter, so enforce it here as well. `0` means no limit.
+        let mut truncated = result
+            .get("warnings")
+            .and_then(|w| w.as_array())
+            .is_some_and(|w| {
+                w.iter()
+                    .filter_map(|v| v.as_str())
+                    .any(|v| v.contains("truncated"))
+            });
+        if let Some(l) = limit.filter(|&l| l > 0) {
+            if series.len() as u64 > l {
+                series.truncate(l as usize);
+                truncated = true;
+            }
+        }
+
+        Ok(SeriesResult { series, truncated })
     }
 
-    /// Get all label values for a specific label name
-    pub async fn get_label_values(&self, label_name: &str) -> Result<Vec<String>, PrometheusError> {
+    /// Get all label values for a specific label name, optionally restricted to series
+    /// matching `match_strings` within `[start, end]` and capped at `limit` values
+    pub async fn get_label_values(
+        &self,
+        label_name: &str,
+        match_strings: Vec<&str>,
+        start: Option<&str>,
+        end: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<Vec<String>, PrometheusError> {
         let url = format!("{}/api/v1/label/{}/values", self.config.url, label_name);
+        let params = selection_params(match_strings, start, end, limit);
 
-        let rb = self.build_get(&url);
+        let rb = self.build_get(&url).query(&params);
         // No rate limiting here previously; preserve behavior
         let response = self.send_request_response(rb, false).await?;
 
@@ -332,17 +977,736 @@ impl PrometheusClient {
             PrometheusError::ParseError(format!("Failed to parse Prometheus response: {}", e))
         })?;
 
-        let mut values = Vec::new();
-        if let Some(data) = result.get("data") {
-            if let Some(data_array) = data.as_array() {
-                for item in data_array {
-                    if let Some(value_str) = item.as_str() {
-                        values.push(value_str.to_string());
+        Ok(string_list(&result))
+    }
+
+    /// List label names, optionally restricted to series matching `match_strings`
+    /// within `[start, end]` and capped at `limit` names
+    pub async fn list_labels(
+        &self,
+        match_strings: Vec<&str>,
+        start: Option<&str>,
+        end: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<Vec<String>, PrometheusError> {
+        let url = format!("{}/api/v1/labels", self.config.url);
+        let params = selection_params(match_strings, start, end, limit);
+
+        let response = self
+            .send_query_request(&url, &as_pairs(&params), false)
+            .await?;
+
+        let result: Value = response.json().await.map_err(|e| {
+            PrometheusError::ParseError(format!("Failed to parse Prometheus response: {}", e))
+        })?;
+
+        Ok(string_list(&result))
+    }
+
+    /// Get all active (pending and firing) alerts
+    pub async fn get_alerts(&self) -> Result<Vec<Alert>, PrometheusError> {
+        let url = format!("{}/api/v1/alerts", self.config.url);
+
+        let rb = self.build_get(&url);
+        let response = self.send_request_response(rb, false).await?;
+
+        let result: Value = response.json().await.map_err(|e| {
+            PrometheusError::ParseError(format!("Failed to parse Prometheus response: {}", e))
+        })?;
+
+        match result.get("data").and_then(|d| d.get("alerts")) {
+            Some(alerts) => serde_json::from_value(alerts.clone())
+                .map_err(|e| PrometheusError::ParseError(format!("Failed to parse alerts: {}", e))),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Get alerting and recording rule groups, optionally filtered
+    pub async fn get_rules(
+        &self,
+        rule_type: Option<&str>,
+        rule_names: Vec<&str>,
+        rule_groups: Vec<&str>,
+        files: Vec<&str>,
+    ) -> Result<Vec<RuleGroup>, PrometheusError> {
+        let url = format!("{}/api/v1/rules", self.config.url);
+
+        let mut params = Vec::new();
+        if let Some(t) = rule_type {
+            params.push(("type", t));
+        }
+        for n in rule_names {
+            params.push(("rule_name[]", n));
+        }
+        for g in rule_groups {
+            params.push(("rule_group[]", g));
+        }
+        for f in files {
+            params.push(("file[]", f));
+        }
+
+        let rb = self.build_get(&url).query(&params);
+        let response = self.send_request_response(rb, false).await?;
+
+        let result: Value = response.json().await.map_err(|e| {
+            PrometheusError::ParseError(format!("Failed to parse Prometheus response: {}", e))
+        })?;
+
+        match result.get("data").and_then(|d| d.get("groups")) {
+            Some(groups) => serde_json::from_value(groups.clone()).map_err(|e| {
+                PrometheusError::ParseError(format!("Failed to parse rule groups: {}", e))
+            }),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Get server status information (build info, runtime info, flags or loaded config)
+    pub async fn get_status(&self, section: StatusSection) -> Result<Value, PrometheusError> {
+        let url = format!("{}/api/v1/status/{}", self.config.url, section.as_str());
+
+        let rb = self.build_get(&url);
+        let response = self.send_request_response(rb, false).await?;
+
+        let result: Value = response.json().await.map_err(|e| {
+            PrometheusError::ParseError(format!("Failed to parse Prometheus response: {}", e))
+        })?;
+
+        Ok(result.get("data").cloned().unwrap_or(Value::Null))
+    }
+
+    /// Get TSDB cardinality statistics, limited to the top `limit` entries per list
+    pub async fn get_tsdb_status(&self, limit: Option<u64>) -> Result<TsdbStatus, PrometheusError> {
+        let url = format!("{}/api/v1/status/tsdb", self.config.url);
+
+        let limit_holder;
+        let mut params: Vec<(&str, &str)> = Vec::new();
+        if let Some(l) = limit {
+            limit_holder = l.to_string();
+            params.push(("limit", &limit_holder));
+        }
+
+        let rb = self.build_get(&url).query(&params);
+        let response = self.send_request_response(rb, false).await?;
+
+        let result: Value = response.json().await.map_err(|e| {
+            PrometheusError::ParseError(format!("Failed to parse Prometheus response: {}", e))
+        })?;
+
+        let data = result.get("data").cloned().unwrap_or(Value::Null);
+        serde_json::from_value(data)
+            .map_err(|e| PrometheusError::ParseError(format!("Failed to parse TSDB status: {}", e)))
+    }
+
+    /// Query exemplars for the series selected by `query` in the given time range
+    pub async fn query_exemplars(
+        &self,
+        query: &str,
+        start: Option<&str>,
+        end: Option<&str>,
+    ) -> Result<Vec<ExemplarSeries>, PrometheusError> {
+        let url = format!("{}/api/v1/query_exemplars", self.config.url);
+
+        let mut params = vec![("query", query)];
+        if let Some(s) = start {
+            params.push(("start", s));
+        }
+        if let Some(e) = end {
+            params.push(("end", e));
+        }
+
+        let rb = self.build_get(&url).query(&params);
+        let response = self.send_request_response(rb, true).await?;
+
+        let result: Value = response.json().await.map_err(|e| {
+            PrometheusError::ParseError(format!("Failed to parse Prometheus response: {}", e))
+        })?;
+
+        let data = match result.get("data") {
+            Some(Value::Null) | None => return Ok(Vec::new()),
+            Some(data) => data.clone(),
+        };
+        let mut series: Vec<ExemplarSeries> = serde_json::from_value(data).map_err(|e| {
+            PrometheusError::ParseError(format!("Failed to parse exemplars: {}", e))
+        })?;
+        for exemplar in series.iter_mut().flat_map(|s| s.exemplars.iter_mut()) {
+            exemplar.trace_id = exemplar.trace_id_label().map(str::to_string);
+        }
+
+        Ok(series)
+    }
+
+    /// Read an instant snapshot of the series matching `match_strings` from `/federate`
+    pub async fn federate(
+        &self,
+        match_strings: Vec<&str>,
+    ) -> Result<Vec<MetricFamily>, PrometheusError> {
+        let url = format!("{}/federate", self.config.url);
+        let params = selection_params(match_strings, None, None, None);
+        let rb = self
+            .build_get(&url)
+            .query(&params)
+            .header(reqwest::header::ACCEPT, "text/plain;version=0.0.4");
+        let response = self.send_request_response(rb, true).await?;
+        let body = response.text().await?;
+
+        parse_text_format(&body).map_err(|e| {
+            PrometheusError::ParseError(format!("Failed to parse federation response: {}", e))
+        })
+    }
+
+    /// Format a PromQL expression into its canonical form via `/api/v1/format_query`
+    pub async fn format_query(&self, query: &str) -> Result<String, PrometheusError> {
+        let data = self.promql_tool_request("format_query", query).await?;
+        data.as_str().map(str::to_string).ok_or_else(|| {
+            PrometheusError::ParseError("Expected a string from format_query".to_string())
+        })
+    }
+
+    /// Parse a PromQL expression into its syntax tree via `/api/v1/parse_query`
+    pub async fn parse_query(&self, query: &str) -> Result<Value, PrometheusError> {
+        self.promql_tool_request("parse_query", query).await
+    }
+
+    /// Create a TSDB snapshot (admin API); returns the snapshot directory name
+    pub async fn snapshot(&self, skip_head: bool) -> Result<String, PrometheusError> {
+        let url = format!("{}/api/v1/admin/tsdb/snapshot", self.config.url);
+        let rb = self
+            .build_request(Method::POST, &url)
+            .query(&[("skip_head", skip_head.to_string())]);
+        let response = self.send_request_response(rb, false).await?;
+
+        let result: Value = response.json().await.map_err(|e| {
+            PrometheusError::ParseError(format!("Failed to parse Prometheus response: {}", e))
+        })?;
+        result
+            .pointer("/data/name")
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| {
+                PrometheusError::ParseError("Snapshot response is missing data.name".to_string())
+            })
+    }
+
+    /// Delete series matching the selectors within an optional time range (admin API)
+    pub async fn delete_series(
+        &self,
+        match_strings: Vec<&str>,
+        start: Option<&str>,
+        end: Option<&str>,
+    ) -> Result<(), PrometheusError> {
+        let url = format!("{}/api/v1/admin/tsdb/delete_series", self.config.url);
+        let params = selection_params(match_strings, start, end, None);
+        let rb = self.build_request(Method::POST, &url).query(&params);
+        self.send_request_response(rb, false).await?;
+        Ok(())
+    }
+
+    /// Remove deleted data from disk and clean up tombstones (admin API)
+    pub async fn clean_tombstones(&self) -> Result<(), PrometheusError> {
+        let url = format!("{}/api/v1/admin/tsdb/clean_tombstones", self.config.url);
+        let rb = self.build_request(Method::POST, &url);
+        self.send_request_response(rb, false).await?;
+        Ok(())
+    }
+
+    /// Call one of the PromQL helper endpoints and return its `data` field.
+    ///
+    /// These endpoints are missing on older Prometheus versions and on many
+    /// compatible backends, so a 404/405 is reported as `Unsupported`.
+    async fn promql_tool_request(
+        &self,
+        endpoint: &str,
+        query: &str,
+    ) -> Result<Value, PrometheusError> {
+        let url = format!("{}/api/v1/{}", self.config.url, endpoint);
+        let response = self
+            .build_get(&url)
+            .query(&[("query", query)])
+            .send()
+            .await?;
+
+        let status = response.status();
+        if status == StatusCode::NOT_FOUND || status == StatusCode::METHOD_NOT_ALLOWED {
+            return Err(PrometheusError::Unsupported(format!(
+                "/api/v1/{} is not available on this backend ({})",
+                endpoint, status
+            )));
+        }
+        let response = Self::check_status(response).await?;
+
+        let result: Value = response.json().await.map_err(|e| {
+            PrometheusError::ParseError(format!("Failed to parse Prometheus response: {}", e))
+        })?;
+        Ok(result.get("data").cloned().unwrap_or(Value::Null))
+    }
+}
+
+/// Build the `match[]`, `start`, `end` and `limit` parameters shared by the
+/// label and series endpoints
+fn selection_params(
+    match_strings: Vec<&str>,
+    start: Option<&str>,
+    end: Option<&str>,
+    limit: Option<u64>,
+) -> Vec<(&'static str, String)> {
+    let mut params = Vec::new();
+    for m in match_strings {
+        params.push(("match[]", m.to_string()));
+    }
+    if let Some(s) = start {
+        params.push(("start", s.to_string()));
+    }
+    if let Some(e) = end {
+        params.push(("end", e.to_string()));
+    }
+    if let Some(l) = limit {
+        params.push(("limit", l.to_string()));
+    }
+    params
+}
+
+/// Build an error from a non-success response body such as
+/// `{"status":"error","errorType":"bad_data","error":"..."}`
+fn parse_error_body(status: StatusCode, body: &str) -> PrometheusError {
+    let parsed: Option<Value> = serde_json::from_str(body).ok();
+    let field = |name: &str| {
+        parsed
+            .as_ref()
+            .and_then(|v| v.get(name))
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+    match (field("errorType"), field("error")) {
+        (Some(error_type), Some(error)) => PrometheusError::from_api_error(&error_type, &error),
+        _ => PrometheusError::ApiError(format!("{} - {}", status, body)),
+    }
+}
+
+/// Borrow owned parameter pairs as `(&str, &str)`
+fn as_pairs<'a>(params: &'a [(&str, String)]) -> Vec<(&'a str, &'a str)> {
+    params.iter().map(|(k, v)| (*k, v.as_str())).collect()
+}
+
+/// Length of the parameters once form/URL-encoded
+fn encoded_len(params: &[(&str, &str)]) -> usize {
+    url::form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(params)
+        .finish()
+        .len()
+}
+
+/// Extract a `data` array of strings from an API response
+fn string_list(result: &Value) -> Vec<String> {
+    let mut values = Vec::new();
+    if let Some(data) = result.get("data") {
+        if let Some(data_array) = data.as_array() {
+            for item in data_array {
+                if let Some(value_str) = item.as_str() {
+                    values.push(value_str.to_string());
+                }
+            }
+        }
+    }
+    values
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::test_server::{serve, serve_once, Response};
+
+    #[test]
+    fn test_parse_native_histogram() {
+        let raw = r#"{
+            "status": "success",
+            "data": {
+                "resultType": "vector",
+                "result": [{
+                    "metric": {"__name__": "http_request_duration_seconds"},
+                    "histogram": [1700000000.5, {
+                        "count": "12",
+                        "sum": "3.4",
+                        "buckets": [
+                            [0, "0.5", "1", "4"],
+                            [1, "-1", "-0.5", "2"],
+                            [3, "0", "0", "6"]
+                        ]
+                    }]
+                }]
+            }
+        }"#;
+        let res: PrometheusQueryResult = serde_json::from_str(raw).unwrap();
+        let series = res.data.result.series();
+        let (ts, h) = series[0].histogram.as_ref().unwrap();
+        assert_eq!(*ts, 1700000000.5);
+        assert_eq!(h.count, "12");
+        assert_eq!(h.buckets.len(), 3);
+        assert!(!h.buckets[0].lower_inclusive && h.buckets[0].upper_inclusive);
+        assert!(h.buckets[1].lower_inclusive && !h.buckets[1].upper_inclusive);
+        assert!(h.buckets[2].lower_inclusive && h.buckets[2].upper_inclusive);
+        assert!(series[0].value.is_none());
+
+        let out = serde_json::to_value(&res).unwrap();
+        assert_eq!(
+            out["data"]["result"][0]["histogram"][1]["buckets"][0]["upper"],
+            "1"
+        );
+    }
+
+    #[test]
+    fn test_parse_scalar_and_string_results() {
+        let raw =
+            r#"{"status":"success","data":{"resultType":"scalar","result":[1700000000,"42"]}}"#;
+        let res: PrometheusQueryResult = serde_json::from_str(raw).unwrap();
+        match &res.data.result {
+            PrometheusResultData::Scalar((ts, v)) => {
+                assert_eq!(*ts, 1700000000.0);
+                assert_eq!(v, "42");
+            }
+            other => panic!("expected scalar, got {:?}", other),
+        }
+        assert!(res.data.result.series().is_empty());
+        let out = serde_json::to_value(&res).unwrap();
+        assert_eq!(out["data"]["resultType"], "scalar");
+        assert_eq!(out["data"]["result"][1], "42");
+
+        let raw =
+            r#"{"status":"success","data":{"resultType":"string","result":[1700000000,"hello"]}}"#;
+        let res: PrometheusQueryResult = serde_json::from_str(raw).unwrap();
+        assert_eq!(res.data.result.result_type(), "string");
+
+        let raw = r#"{"status":"success","data":{"resultType":"matrix","result":[{"metric":{},"values":[[1,"1"],[2,"2"]]}]}}"#;
+        let res: PrometheusQueryResult = serde_json::from_str(raw).unwrap();
+        assert!(matches!(res.data.result, PrometheusResultData::Matrix(_)));
+        assert_eq!(
+            res.data.result.series()[0].values.as_ref().unwrap().len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_parse_error_body() {
+        let err = parse_error_body(
+            StatusCode::BAD_REQUEST,
+            r#"{"status":"error","errorType":"bad_data","error":"1:5: parse error: unexpected end of input"}"#,
+        );
+        assert!(matches!(err, PrometheusError::BadData(ref m) if m.contains("parse error")));
+        assert!(!err.is_retryable());
+
+        let err = parse_error_body(
+            StatusCode::SERVICE_UNAVAILABLE,
+            r#"{"status":"error","errorType":"timeout","error":"query timed out in expression evaluation"}"#,
+        );
+        assert!(matches!(err, PrometheusError::Timeout(_)));
+        assert!(err.is_retryable());
+
+        let err = parse_error_body(StatusCode::BAD_GATEWAY, "<html>bad gateway</html>");
+        assert_eq!(
+            err.to_string(),
+            "Prometheus API error: 502 Bad Gateway - <html>bad gateway</html>"
+        );
+    }
+
+    #[test]
+    fn test_parse_query_stats() {
+        let raw = r#"{
+            "status": "success",
+            "data": {
+                "resultType": "vector",
+                "result": [],
+                "stats": {
+                    "timings": {
+                        "evalTotalTime": 0.0123,
+                        "resultSortTime": 0,
+                        "queryPreparationTime": 0.001,
+                        "innerEvalTime": 0.011,
+                        "execQueueTime": 0.00002,
+                        "execTotalTime": 0.0124
+                    },
+                    "samples": {
+                        "totalQueryableSamplesPerStep": [[1700000000, 10]],
+                        "totalQueryableSamples": 1500,
+                        "peakSamples": 120
                     }
                 }
             }
+        }"#;
+        let res: PrometheusQueryResult = serde_json::from_str(raw).unwrap();
+        let stats = res.data.stats.unwrap();
+        assert_eq!(stats.samples.total_queryable_samples, 1500);
+        assert_eq!(stats.samples.peak_samples, 120);
+        assert_eq!(stats.timings.exec_total_time, 0.0124);
+
+        let opts = QueryOptions {
+            stats: true,
+            timeout: Some("5s".into()),
+            limit: Some(10),
+            ..Default::default()
+        };
+        assert_eq!(
+            opts.params(),
+            vec![
+                ("stats", "all".to_string()),
+                ("timeout", "5s".to_string()),
+                ("limit", "10".to_string())
+            ]
+        );
+        assert!(opts.thanos_options_set().is_empty());
+
+        let opts = QueryOptions {
+            dedup: Some(false),
+            partial_response: Some(true),
+            max_source_resolution: Some("auto".into()),
+            engine: Some("thanos".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            opts.params(),
+            vec![
+                ("dedup", "false".to_string()),
+                ("partial_response", "true".to_string()),
+                ("max_source_resolution", "auto".to_string()),
+                ("engine", "thanos".to_string())
+            ]
+        );
+        assert_eq!(opts.thanos_options_set().len(), 4);
+    }
+
+    #[test]
+    fn test_should_post_threshold() {
+        let cfg = PrometheusConfig::default().with_post_threshold_bytes(32);
+        let client = PrometheusClient::new(cfg).unwrap();
+        assert!(!client.should_post(&[("query", "up")]));
+        let long = format!("up{{job=~\"{}\"}}", vec!["a"; 20].join("|"));
+        assert!(client.should_post(&[("query", &long)]));
+
+        let cfg = PrometheusConfig::default().with_always_post(true);
+        let client = PrometheusClient::new(cfg).unwrap();
+        assert!(client.should_post(&[("query", "up")]));
+        client.post_rejected.store(true, Ordering::Relaxed);
+        assert!(!client.should_post(&[("query", "up")]));
+    }
+
+    #[tokio::test]
+    async fn test_post_falls_back_to_get_on_405() {
+        // Minimal backend that rejects POST and answers GET with an empty vector
+        let (url, server) = serve(3, |request| {
+            if request.method() == "POST" {
+                Response::new("405 Method Not Allowed", "")
+            } else {
+                Response::json(r#"{"status":"success","data":{"resultType":"vector","result":[]}}"#)
+            }
+        })
+        .await;
+
+        let cfg = PrometheusConfig::with_url(url)
+            .with_always_post(true)
+            .with_retries(1);
+        let client = PrometheusClient::new(cfg).unwrap();
+        let res = client
+            .query("up", None, &QueryOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(res.status, "success");
+        // Second query goes straight to GET
+        client
+            .query("up", None, &QueryOptions::default())
+            .await
+            .unwrap();
+
+        let methods: Vec<String> = server
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| r.method().to_string())
+            .collect();
+        assert_eq!(methods, vec!["POST", "GET", "GET"]);
+    }
+
+    #[tokio::test]
+    async fn test_tenant_header() {
+        let (url, server) = serve(2, |_| {
+            Response::json(r#"{"status":"success","data":["up"]}"#)
+        })
+        .await;
+
+        let cfg = PrometheusConfig::with_url(url).with_tenant("team-a");
+        let client = PrometheusClient::new(cfg).unwrap();
+        client.list_metrics().await.unwrap();
+        tenant::with_tenant(Some("team-b|team-c".into()), client.list_metrics())
+            .await
+            .unwrap();
+
+        let tenants: Vec<String> = server
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| r.header("x-scope-orgid").unwrap_or("").to_string())
+            .collect();
+        assert_eq!(tenants, vec!["team-a", "team-b|team-c"]);
+    }
+
+    #[tokio::test]
+    async fn test_get_series_limit() {
+        let (url, server) = serve(2, |_| {
+            Response::json(r#"{"status":"success","data":[{"__name__":"up","job":"a"},{"__name__":"up","job":"b"},{"__name__":"up","job":"c"}]}"#)
+        })
+        .await;
+
+        let client = PrometheusClient::new(PrometheusConfig::with_url(url)).unwrap();
+        // Like Prometheus, a limit of 0 disables the limit
+        let result = client
+            .get_series(vec!["up"], None, None, Some(0))
+            .await
+            .unwrap();
+        assert_eq!(result.series.len(), 3);
+        assert!(!result.truncated);
+
+        let result = client
+            .get_series(vec!["up"], None, None, Some(2))
+            .await
+            .unwrap();
+        assert_eq!(result.series.len(), 2);
+        assert!(result.truncated);
+        server.await.unwrap();
+    }
+
+    const EMPTY_LIST: &str = r#"{"status":"success","data":[]}"#;
+
+    #[tokio::test]
+    async fn test_static_headers_and_proxy() {
+        let token_path =
+            std::env::temp_dir().join(format!("prometheus-mcp-header-{}", std::process::id()));
+        std::fs::write(&token_path, "s3cret\n").unwrap();
+
+        // Requests to the Prometheus URL go through the proxy with the static headers
+        let (proxy_url, server) = serve_once(Response::json(EMPTY_LIST)).await;
+        let cfg = PrometheusConfig::with_url("http://prometheus.example:9090".into())
+            .with_header("X-Team=observability".parse().unwrap())
+            .with_header(
+                format!("X-Gateway-Key=file:{}", token_path.display())
+                    .parse()
+                    .unwrap(),
+            )
+            .with_proxy(Some(proxy_url), None, vec![]);
+        PrometheusClient::new(cfg)
+            .unwrap()
+            .list_metrics()
+            .await
+            .unwrap();
+        let req = server.await.unwrap();
+        assert!(req.head.starts_with(
+            "GET http://prometheus.example:9090/api/v1/label/__name__/values HTTP/1.1"
+        ));
+        assert_eq!(req.header("x-team"), Some("observability"));
+        assert_eq!(req.header("x-gateway-key"), Some("s3cret"));
+
+        // Hosts on the no_proxy list are reached directly
+        let (url, server) = serve_once(Response::json(EMPTY_LIST)).await;
+        let cfg = PrometheusConfig::with_url(url).with_proxy(
+            Some("http://127.0.0.1:9".into()),
+            None,
+            vec!["127.0.0.1".into()],
+        );
+        PrometheusClient::new(cfg)
+            .unwrap()
+            .list_metrics()
+            .await
+            .unwrap();
+        assert!(server
+            .await
+            .unwrap()
+            .head
+            .starts_with("GET /api/v1/label/__name__/values HTTP/1.1"));
+
+        let cfg = PrometheusConfig::default()
+            .with_header("X-Key=env:PROMETHEUS_MCP_UNSET_VAR".parse().unwrap());
+        assert!(PrometheusClient::new(cfg).is_err());
+        std::fs::remove_file(&token_path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_rule_groups() {
+        let raw = serde_json::json!([{
+            "name": "example",
+            "file": "/rules.yaml",
+            "interval": 60,
+            "evaluationTime": 0.0012,
+            "lastEvaluation": "2025-09-27T12:00:00Z",
+            "rules": [
+                {
+                    "type": "alerting",
+                    "name": "HighErrorRate",
+                    "query": "job:request_errors:rate5m > 0.5",
+                    "duration": 600,
+                    "labels": {"severity": "page"},
+                    "annotations": {"summary": "High error rate"},
+                    "alerts": [{
+                        "labels": {"alertname": "HighErrorRate"},
+                        "annotations": {},
+                        "state": "firing",
+                        "activeAt": "2025-09-27T11:50:00Z",
+                        "value": "7e-01"
+                    }],
+                    "health": "ok",
+                    "evaluationTime": 0.0004,
+                    "state": "firing"
+                },
+                {
+                    "type": "recording",
+                    "name": "job:request_errors:rate5m",
+                    "query": "sum by (job) (rate(request_errors_total[5m]))",
+                    "health": "err",
+                    "lastError": "many-to-many matching not allowed"
+                }
+            ]
+        }]);
+        let groups: Vec<RuleGroup> = serde_json::from_value(raw).unwrap();
+        assert_eq!(groups[0].rules.len(), 2);
+        match &groups[0].rules[0] {
+            Rule::Alerting(r) => {
+                assert_eq!(r.duration, 600.0);
+                assert_eq!(r.alerts[0].state, "firing");
+            }
+            other => panic!("expected alerting rule, got {:?}", other),
+        }
+        match &groups[0].rules[1] {
+            Rule::Recording(r) => assert!(r.last_error.is_some()),
+            other => panic!("expected recording rule, got {:?}", other),
         }
+    }
+
+    #[test]
+    fn test_parse_tsdb_status() {
+        let raw = serde_json::json!({
+            "headStats": {
+                "numSeries": 508,
+                "numLabelPairs": 1234,
+                "chunkCount": 937,
+                "minTime": 1591516800000i64,
+                "maxTime": 1598896800143i64
+            },
+            "seriesCountByMetricName": [{"name": "net_conntrack_dialer_conn_failed_total", "value": 20}],
+            "labelValueCountByLabelName": [{"name": "__name__", "value": 211}],
+            "memoryInBytesByLabelName": [{"name": "__name__", "value": 8266}],
+            "seriesCountByLabelValuePair": [{"name": "job=prometheus", "value": 425}]
+        });
+        let status: TsdbStatus = serde_json::from_value(raw).unwrap();
+        assert_eq!(status.head_stats.unwrap().num_series, 508);
+        assert_eq!(
+            status.series_count_by_label_value_pair[0].name,
+            "job=prometheus"
+        );
+    }
 
-        Ok(values)
+    #[test]
+    fn test_exemplar_trace_id_label() {
+        let raw = serde_json::json!({
+            "labels": {"traceID": "EpTxMJ40fUus7aGY"},
+            "value": "6",
+            "timestamp": 1600096945.479
+        });
+        let exemplar: Exemplar = serde_json::from_value(raw).unwrap();
+        assert_eq!(exemplar.trace_id_label(), Some("EpTxMJ40fUus7aGY"));
     }
 }
@@ -1,6 +1,116 @@
 use std::env;
 use std::time::Duration;
 
+use crate::mcp::prometheus_client::PrometheusError;
+use crate::mcp::tenant::DEFAULT_TENANT_HEADER;
+
+/// Kind of server behind the Prometheus URL, for backend-specific query parameters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BackendFlavor {
+    #[default]
+    Prometheus,
+    /// Thanos Query: adds `dedup`, `partial_response`, `max_source_resolution` and `engine`
+    Thanos,
+}
+
+impl BackendFlavor {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BackendFlavor::Prometheus => "prometheus",
+            BackendFlavor::Thanos => "thanos",
+        }
+    }
+}
+
+impl std::str::FromStr for BackendFlavor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "prometheus" => Ok(BackendFlavor::Prometheus),
+            "thanos" => Ok(BackendFlavor::Thanos),
+            other => Err(format!(
+                "unknown backend flavor '{}' (expected prometheus or thanos)",
+                other
+            )),
+        }
+    }
+}
+
+/// Headers that cannot be set as static headers; they are managed by the client
+const RESERVED_HEADERS: &[&str] = &[
+    "authorization",
+    "host",
+    "content-type",
+    "content-length",
+    "content-encoding",
+];
+
+/// Where the value of a static request header comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeaderSource {
+    Value(String),
+    /// Environment variable, read at startup
+    Env(String),
+    /// File, re-read when it changes
+    File(String),
+}
+
+/// Header sent on every Prometheus request: `Name=value`, `Name=env:VAR` or `Name=file:/path`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpHeader {
+    pub name: String,
+    pub source: HeaderSource,
+}
+
+impl std::str::FromStr for HttpHeader {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = s
+            .split_once('=')
+            .ok_or_else(|| format!("invalid header '{}' (expected Name=value)", s))?;
+        let name = name.trim();
+        reqwest::header::HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| format!("invalid header name '{}'", name))?;
+        if RESERVED_HEADERS.contains(&name.to_ascii_lowercase().as_str()) {
+            return Err(format!(
+                "header '{}' cannot be set as a static header",
+                name
+            ));
+        }
+        let value = value.trim();
+        let source = if let Some(var) = value.strip_prefix("env:") {
+            HeaderSource::Env(var.to_string())
+        } else if let Some(path) = value.strip_prefix("file:") {
+            HeaderSource::File(path.to_string())
+        } else {
+            reqwest::header::HeaderValue::from_str(value)
+                .map_err(|_| format!("invalid value for header '{}'", name))?;
+            HeaderSource::Value(value.to_string())
+        };
+        Ok(HttpHeader {
+            name: name.to_string(),
+            source,
+        })
+    }
+}
+
+/// TLS settings for HTTPS connections, mirroring Prometheus' `tls_config`
+#[derive(Debug, Clone, Default)]
+pub struct TlsConfig {
+    /// PEM CA bundle trusted instead of the system roots
+    pub ca_file: Option<String>,
+    /// PEM client certificate for mutual TLS
+    pub cert_file: Option<String>,
+    /// PEM private key of the client certificate (PKCS#8, PKCS#1 or SEC1)
+    pub key_file: Option<String>,
+    /// Name to verify the server certificate against (and send as SNI) instead of the URL host
+    pub server_name: Option<String>,
+    /// Skip server certificate verification
+    pub insecure_skip_verify: bool,
+}
+
 /// Configuration for Prometheus
 #[derive(Debug, Clone)]
 pub struct PrometheusConfig {
@@ -20,6 +130,41 @@ pub struct PrometheusConfig {
     pub username: Option<String>,
     /// Basic auth password
     pub password: Option<String>,
+    /// Bearer token sent in the `Authorization` header
+    pub bearer_token: Option<String>,
+    /// File to read the bearer token from (e.g. a Kubernetes service account token)
+    pub bearer_token_file: Option<String>,
+    /// Re-read the bearer token file at least this often, besides when it changes
+    pub bearer_token_refresh: Duration,
+    /// TLS settings for HTTPS URLs
+    pub tls: TlsConfig,
+    /// Static headers sent on every request
+    pub headers: Vec<HttpHeader>,
+    /// Proxy for `http://` URLs (credentials may be given in the URL)
+    pub http_proxy: Option<String>,
+    /// Proxy for `https://` URLs
+    pub https_proxy: Option<String>,
+    /// Hosts, domains and CIDRs reached without the proxy
+    pub no_proxy: Vec<String>,
+    /// Send query, range, series and label requests as form-encoded POST once the
+    /// encoded parameters exceed this many bytes
+    pub post_threshold_bytes: usize,
+    /// Always use POST for those requests, regardless of size
+    pub always_post: bool,
+    /// Register the TSDB admin tools (snapshot, delete_series, clean_tombstones)
+    pub enable_admin_tools: bool,
+    /// Remote-read endpoint (e.g. `http://prometheus:9090/api/v1/read`) used for raw sample reads
+    pub remote_read_url: Option<String>,
+    /// Tenant sent on every request (`a|b` queries several tenants)
+    pub tenant_id: Option<String>,
+    /// Header carrying the tenant, `X-Scope-OrgID` unless the backend expects another
+    pub tenant_header: String,
+    /// Tenants a tool call may select with its `tenant` argument
+    pub allowed_tenants: Vec<String>,
+    /// Backend behind `url`
+    pub flavor: BackendFlavor,
+    /// Hosts the scrape tool may fetch from; empty disables it
+    pub scrape_allowed_hosts: Vec<String>,
 }
 
 impl Default for PrometheusConfig {
@@ -33,13 +178,31 @@ impl Default for PrometheusConfig {
             cache_ttl_secs: None,
             username: None,
             password: None,
+            bearer_token: None,
+            bearer_token_file: None,
+            bearer_token_refresh: Duration::from_secs(60),
+            tls: TlsConfig::default(),
+            headers: Vec::new(),
+            http_proxy: None,
+            https_proxy: None,
+            no_proxy: Vec::new(),
+            post_threshold_bytes: 4096,
+            always_post: false,
+            enable_admin_tools: false,
+            remote_read_url: None,
+            tenant_id: None,
+            tenant_header: DEFAULT_TENANT_HEADER.to_string(),
+            allowed_tenants: Vec::new(),
+            flavor: BackendFlavor::Prometheus,
+            scrape_allowed_hosts: Vec::new(),
         }
     }
 }
 
 impl PrometheusConfig {
-    /// Create a new PrometheusConfig from environment variables
-    pub fn from_env() -> Self {
+    /// Create a new PrometheusConfig from environment variables; fails on invalid
+    /// `PROMETHEUS_HEADERS` entries
+    pub fn from_env() -> Result<Self, PrometheusError> {
         let url =
             env::var("PROMETHEUS_URL").unwrap_or_else(|_| "http://localhost:9090".to_string());
 
@@ -68,8 +231,68 @@ impl PrometheusConfig {
 
         let username = env::var("PROMETHEUS_USERNAME").ok();
         let password = env::var("PROMETHEUS_PASSWORD").ok();
+        let bearer_token = env::var("PROMETHEUS_BEARER_TOKEN").ok();
+        let bearer_token_file = env::var("PROMETHEUS_BEARER_TOKEN_FILE").ok();
+        let bearer_token_refresh_secs = env::var("PROMETHEUS_BEARER_TOKEN_REFRESH_SECS")
+            .ok()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(60);
 
-        Self {
+        let tls = TlsConfig {
+            ca_file: env::var("PROMETHEUS_TLS_CA_FILE").ok(),
+            cert_file: env::var("PROMETHEUS_TLS_CERT_FILE").ok(),
+            key_file: env::var("PROMETHEUS_TLS_KEY_FILE").ok(),
+            server_name: env::var("PROMETHEUS_TLS_SERVER_NAME").ok(),
+            insecure_skip_verify: env::var("PROMETHEUS_TLS_INSECURE_SKIP_VERIFY")
+                .ok()
+                .and_then(|s| s.parse::<bool>().ok())
+                .unwrap_or(false),
+        };
+
+        let headers = match env::var("PROMETHEUS_HEADERS") {
+            Ok(s) => parse_headers(&s)?,
+            Err(_) => Vec::new(),
+        };
+        let http_proxy = env::var("PROMETHEUS_HTTP_PROXY").ok();
+        let https_proxy = env::var("PROMETHEUS_HTTPS_PROXY").ok();
+        let no_proxy = env::var("PROMETHEUS_NO_PROXY")
+            .map(|s| parse_list(&s))
+            .unwrap_or_default();
+
+        let post_threshold_bytes = env::var("PROMETHEUS_POST_THRESHOLD_BYTES")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .unwrap_or(4096);
+
+        let always_post = env::var("PROMETHEUS_ALWAYS_POST")
+            .ok()
+            .and_then(|s| s.parse::<bool>().ok())
+            .unwrap_or(false);
+
+        let enable_admin_tools = env::var("PROMETHEUS_ENABLE_ADMIN_TOOLS")
+            .ok()
+            .and_then(|s| s.parse::<bool>().ok())
+            .unwrap_or(false);
+
+        let remote_read_url = env::var("PROMETHEUS_REMOTE_READ_URL").ok();
+
+        let tenant_id = env::var("PROMETHEUS_TENANT_ID").ok();
+        let tenant_header = env::var("PROMETHEUS_TENANT_HEADER")
+            .unwrap_or_else(|_| DEFAULT_TENANT_HEADER.to_string());
+        let allowed_tenants = env::var("PROMETHEUS_ALLOWED_TENANTS")
+            .map(|s| parse_list(&s))
+            .unwrap_or_default();
+
+        let flavor = match env::var("PROMETHEUS_FLAVOR") {
+            Ok(s) => parse_flavor(&s)?,
+            Err(_) => BackendFlavor::default(),
+        };
+
+        let scrape_allowed_hosts = env::var("PROMETHEUS_SCRAPE_ALLOWED_HOSTS")
+            .map(|s| parse_list(&s))
+            .unwrap_or_default();
+
+        Ok(Self {
             url,
             timeout: Duration::from_secs(timeout_secs),
             retries,
@@ -78,12 +301,29 @@ impl PrometheusConfig {
             cache_ttl_secs,
             username,
             password,
-        }
+            bearer_token,
+            bearer_token_file,
+            bearer_token_refresh: Duration::from_secs(bearer_token_refresh_secs),
+            tls,
+            headers,
+            http_proxy,
+            https_proxy,
+            no_proxy,
+            post_threshold_bytes,
+            always_post,
+            enable_admin_tools,
+            remote_read_url,
+            tenant_id,
+            tenant_header,
+            allowed_tenants,
+            flavor,
+            scrape_allowed_hosts,
+        })
     }
 
     /// Create a new PrometheusConfig from a map of values (useful for tests)
     #[cfg(test)]
-    pub fn from_map(map: &std::collections::HashMap<&str, &str>) -> Self {
+    pub fn from_map(map: &std::collections::HashMap<&str, &str>) -> Result<Self, PrometheusError> {
         let url = map
             .get("PROMETHEUS_URL")
             .map(|s| s.to_string())
@@ -114,8 +354,75 @@ impl PrometheusConfig {
 
         let username = map.get("PROMETHEUS_USERNAME").map(|s| s.to_string());
         let password = map.get("PROMETHEUS_PASSWORD").map(|s| s.to_string());
+        let bearer_token = map.get("PROMETHEUS_BEARER_TOKEN").map(|s| s.to_string());
+        let bearer_token_file = map
+            .get("PROMETHEUS_BEARER_TOKEN_FILE")
+            .map(|s| s.to_string());
+        let bearer_token_refresh_secs = map
+            .get("PROMETHEUS_BEARER_TOKEN_REFRESH_SECS")
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(60);
 
-        Self {
+        let tls = TlsConfig {
+            ca_file: map.get("PROMETHEUS_TLS_CA_FILE").map(|s| s.to_string()),
+            cert_file: map.get("PROMETHEUS_TLS_CERT_FILE").map(|s| s.to_string()),
+            key_file: map.get("PROMETHEUS_TLS_KEY_FILE").map(|s| s.to_string()),
+            server_name: map.get("PROMETHEUS_TLS_SERVER_NAME").map(|s| s.to_string()),
+            insecure_skip_verify: map
+                .get("PROMETHEUS_TLS_INSECURE_SKIP_VERIFY")
+                .and_then(|s| s.parse::<bool>().ok())
+                .unwrap_or(false),
+        };
+
+        let headers = match map.get("PROMETHEUS_HEADERS") {
+            Some(s) => parse_headers(s)?,
+            None => Vec::new(),
+        };
+        let http_proxy = map.get("PROMETHEUS_HTTP_PROXY").map(|s| s.to_string());
+        let https_proxy = map.get("PROMETHEUS_HTTPS_PROXY").map(|s| s.to_string());
+        let no_proxy = map
+            .get("PROMETHEUS_NO_PROXY")
+            .map(|s| parse_list(s))
+            .unwrap_or_default();
+
+        let post_threshold_bytes = map
+            .get("PROMETHEUS_POST_THRESHOLD_BYTES")
+            .and_then(|s| s.parse::<usize>().ok())
+            .unwrap_or(4096);
+
+        let always_post = map
+            .get("PROMETHEUS_ALWAYS_POST")
+            .and_then(|s| s.parse::<bool>().ok())
+            .unwrap_or(false);
+
+        let enable_admin_tools = map
+            .get("PROMETHEUS_ENABLE_ADMIN_TOOLS")
+            .and_then(|s| s.parse::<bool>().ok())
+            .unwrap_or(false);
+
+        let remote_read_url = map.get("PROMETHEUS_REMOTE_READ_URL").map(|s| s.to_string());
+
+        let tenant_id = map.get("PROMETHEUS_TENANT_ID").map(|s| s.to_string());
+        let tenant_header = map
+            .get("PROMETHEUS_TENANT_HEADER")
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| DEFAULT_TENANT_HEADER.to_string());
+        let allowed_tenants = map
+            .get("PROMETHEUS_ALLOWED_TENANTS")
+            .map(|s| parse_list(s))
+            .unwrap_or_default();
+
+        let flavor = match map.get("PROMETHEUS_FLAVOR") {
+            Some(s) => parse_flavor(s)?,
+            None => BackendFlavor::default(),
+        };
+
+        let scrape_allowed_hosts = map
+            .get("PROMETHEUS_SCRAPE_ALLOWED_HOSTS")
+            .map(|s| parse_list(s))
+            .unwrap_or_default();
+
+        Ok(Self {
             url,
             timeout: Duration::from_secs(timeout_secs),
             retries,
@@ -124,7 +431,24 @@ impl PrometheusConfig {
             cache_ttl_secs,
             username,
             password,
-        }
+            bearer_token,
+            bearer_token_file,
+            bearer_token_refresh: Duration::from_secs(bearer_token_refresh_secs),
+            tls,
+            headers,
+            http_proxy,
+            https_proxy,
+            no_proxy,
+            post_threshold_bytes,
+            always_post,
+            enable_admin_tools,
+            remote_read_url,
+            tenant_id,
+            tenant_header,
+            allowed_tenants,
+            flavor,
+            scrape_allowed_hosts,
+        })
     }
 
     /// Create a new PrometheusConfig with the given URL
@@ -182,6 +506,145 @@ impl PrometheusConfig {
         self.password = Some(password.into());
         self
     }
+
+    /// Set a bearer token
+    #[allow(dead_code)]
+    pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.bearer_token = Some(token.into());
+        self
+    }
+
+    /// Read the bearer token from a file, reloading it when it changes
+    #[allow(dead_code)]
+    pub fn with_bearer_token_file(mut self, path: impl Into<String>) -> Self {
+        self.bearer_token_file = Some(path.into());
+        self
+    }
+
+    /// Set how often the bearer token file is re-read at the latest (seconds)
+    #[allow(dead_code)]
+    pub fn with_bearer_token_refresh_secs(mut self, secs: u64) -> Self {
+        self.bearer_token_refresh = Duration::from_secs(secs);
+        self
+    }
+
+    /// Set the TLS settings
+    #[allow(dead_code)]
+    pub fn with_tls(mut self, tls: TlsConfig) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    /// Add a static header sent on every request
+    #[allow(dead_code)]
+    pub fn with_header(mut self, header: HttpHeader) -> Self {
+        self.headers.push(header);
+        self
+    }
+
+    /// Set the proxies for HTTP and HTTPS URLs and the hosts that bypass them
+    #[allow(dead_code)]
+    pub fn with_proxy(
+        mut self,
+        http_proxy: Option<String>,
+        https_proxy: Option<String>,
+        no_proxy: Vec<String>,
+    ) -> Self {
+        self.http_proxy = http_proxy;
+        self.https_proxy = https_proxy;
+        self.no_proxy = no_proxy;
+        self
+    }
+
+    /// Set the size (bytes of encoded parameters) above which requests switch to POST
+    #[allow(dead_code)]
+    pub fn with_post_threshold_bytes(mut self, bytes: usize) -> Self {
+        self.post_threshold_bytes = bytes;
+        self
+    }
+
+    /// Always send query requests as POST
+    #[allow(dead_code)]
+    pub fn with_always_post(mut self, always_post: bool) -> Self {
+        self.always_post = always_post;
+        self
+    }
+
+    /// Enable the TSDB admin tools
+    #[allow(dead_code)]
+    pub fn with_admin_tools(mut self, enabled: bool) -> Self {
+        self.enable_admin_tools = enabled;
+        self
+    }
+
+    /// Set the remote-read endpoint
+    #[allow(dead_code)]
+    pub fn with_remote_read_url(mut self, url: impl Into<String>) -> Self {
+        self.remote_read_url = Some(url.into());
+        self
+    }
+
+    /// Set the tenant sent on every request
+    #[allow(dead_code)]
+    pub fn with_tenant(mut self, tenant_id: impl Into<String>) -> Self {
+        self.tenant_id = Some(tenant_id.into());
+        self
+    }
+
+    /// Set the header carrying the tenant
+    #[allow(dead_code)]
+    pub fn with_tenant_header(mut self, header: impl Into<String>) -> Self {
+        self.tenant_header = header.into();
+        self
+    }
+
+    /// Set the tenants a tool call may select
+    #[allow(dead_code)]
+    pub fn with_allowed_tenants(mut self, tenants: Vec<String>) -> Self {
+        self.allowed_tenants = tenants;
+        self
+    }
+
+    /// Set the backend flavor
+    #[allow(dead_code)]
+    pub fn with_flavor(mut self, flavor: BackendFlavor) -> Self {
+        self.flavor = flavor;
+        self
+    }
+
+    /// Set the hosts the scrape tool may fetch from
+    #[allow(dead_code)]
+    pub fn with_scrape_allowed_hosts(mut self, hosts: Vec<String>) -> Self {
+        self.scrape_allowed_hosts = hosts;
+        self
+    }
+}
+
+/// Split a comma-separated list, dropping empty entries
+fn parse_list(s: &str) -> Vec<String> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Parse headers given one per line, so values may contain commas
+fn parse_headers(s: &str) -> Result<Vec<HttpHeader>, PrometheusError> {
+    s.lines()
+        .map(str::trim)
+        .filter(|h| !h.is_empty())
+        .map(|h| {
+            h.parse::<HttpHeader>().map_err(|e| {
+                PrometheusError::BuildClientError(format!("invalid PROMETHEUS_HEADERS: {}", e))
+            })
+        })
+        .collect()
+}
+
+fn parse_flavor(s: &str) -> Result<BackendFlavor, PrometheusError> {
+    s.parse::<BackendFlavor>()
+        .map_err(|e| PrometheusError::BuildClientError(format!("invalid PROMETHEUS_FLAVOR: {}", e)))
 }
 
 #[cfg(test)]
@@ -193,8 +656,23 @@ mod tests {
     fn test_from_env_defaults_and_overrides() {
         // Use from_map to avoid mutating global process env in tests
         let empty: HashMap<&str, &str> = HashMap::new();
-        let cfg = PrometheusConfig::from_map(&empty);
+        let cfg = PrometheusConfig::from_map(&empty).unwrap();
         assert_eq!(cfg.url, "http://localhost:9090");
+        assert_eq!(cfg.post_threshold_bytes, 4096);
+        assert!(!cfg.always_post);
+        assert!(!cfg.enable_admin_tools);
+        assert!(cfg.remote_read_url.is_none());
+        assert!(cfg.tenant_id.is_none());
+        assert_eq!(cfg.tenant_header, "X-Scope-OrgID");
+        assert!(cfg.allowed_tenants.is_empty());
+        assert!(cfg.scrape_allowed_hosts.is_empty());
+        assert_eq!(cfg.bearer_token_refresh, std::time::Duration::from_secs(60));
+        assert!(cfg.tls.ca_file.is_none());
+        assert!(!cfg.tls.insecure_skip_verify);
+        assert!(cfg.headers.is_empty());
+        assert!(cfg.http_proxy.is_none());
+        assert!(cfg.no_proxy.is_empty());
+        assert_eq!(cfg.flavor, BackendFlavor::Prometheus);
 
         let mut vars: HashMap<&str, &str> = HashMap::new();
         vars.insert("PROMETHEUS_URL", "http://example:9090");
@@ -205,8 +683,37 @@ mod tests {
         vars.insert("PROMETHEUS_CACHE_TTL_SECS", "30");
         vars.insert("PROMETHEUS_USERNAME", "u");
         vars.insert("PROMETHEUS_PASSWORD", "p");
+        vars.insert("PROMETHEUS_BEARER_TOKEN_FILE", "/var/run/secrets/token");
+        vars.insert("PROMETHEUS_BEARER_TOKEN_REFRESH_SECS", "300");
+        vars.insert("PROMETHEUS_TLS_CA_FILE", "/etc/prometheus/ca.pem");
+        vars.insert("PROMETHEUS_TLS_SERVER_NAME", "prometheus.internal");
+        vars.insert("PROMETHEUS_TLS_INSECURE_SKIP_VERIFY", "true");
+        vars.insert(
+            "PROMETHEUS_HEADERS",
+            "X-Team=observability, a, b\n\n  X-Api-Key=env:GATEWAY_KEY\n",
+        );
+        vars.insert("PROMETHEUS_HTTPS_PROXY", "http://proxy.corp:3128");
+        vars.insert(
+            "PROMETHEUS_NO_PROXY",
+            "localhost, .svc.cluster.local,10.0.0.0/8",
+        );
+        vars.insert("PROMETHEUS_POST_THRESHOLD_BYTES", "1024");
+        vars.insert("PROMETHEUS_ALWAYS_POST", "true");
+        vars.insert("PROMETHEUS_ENABLE_ADMIN_TOOLS", "true");
+        vars.insert(
+            "PROMETHEUS_REMOTE_READ_URL",
+            "http://example:9090/api/v1/read",
+        );
+        vars.insert("PROMETHEUS_TENANT_ID", "team-a|team-b");
+        vars.insert("PROMETHEUS_TENANT_HEADER", "THANOS-TENANT");
+        vars.insert("PROMETHEUS_ALLOWED_TENANTS", "team-a, team-b,,team-c");
+        vars.insert(
+            "PROMETHEUS_SCRAPE_ALLOWED_HOSTS",
+            "node-exporter:9100, .monitoring.svc",
+        );
+        vars.insert("PROMETHEUS_FLAVOR", "Thanos");
 
-        let cfg = PrometheusConfig::from_map(&vars);
+        let cfg = PrometheusConfig::from_map(&vars).unwrap();
         assert_eq!(cfg.url, "http://example:9090");
         assert_eq!(cfg.timeout, std::time::Duration::from_secs(5));
         assert_eq!(cfg.retries, 2);
@@ -215,5 +722,83 @@ mod tests {
         assert_eq!(cfg.cache_ttl_secs, Some(30));
         assert_eq!(cfg.username.as_deref(), Some("u"));
         assert_eq!(cfg.password.as_deref(), Some("p"));
+        assert!(cfg.bearer_token.is_none());
+        assert_eq!(
+            cfg.bearer_token_file.as_deref(),
+            Some("/var/run/secrets/token")
+        );
+        assert_eq!(
+            cfg.bearer_token_refresh,
+            std::time::Duration::from_secs(300)
+        );
+        assert_eq!(cfg.tls.ca_file.as_deref(), Some("/etc/prometheus/ca.pem"));
+        assert!(cfg.tls.cert_file.is_none());
+        assert_eq!(cfg.tls.server_name.as_deref(), Some("prometheus.internal"));
+        assert!(cfg.tls.insecure_skip_verify);
+        // One header per line; values keep their commas
+        assert_eq!(
+            cfg.headers,
+            vec![
+                HttpHeader {
+                    name: "X-Team".into(),
+                    source: HeaderSource::Value("observability, a, b".into()),
+                },
+                HttpHeader {
+                    name: "X-Api-Key".into(),
+                    source: HeaderSource::Env("GATEWAY_KEY".into()),
+                },
+            ]
+        );
+        assert!(cfg.http_proxy.is_none());
+        assert_eq!(cfg.https_proxy.as_deref(), Some("http://proxy.corp:3128"));
+        assert_eq!(
+            cfg.no_proxy,
+            vec!["localhost", ".svc.cluster.local", "10.0.0.0/8"]
+        );
+        assert_eq!(cfg.post_threshold_bytes, 1024);
+        assert!(cfg.always_post);
+        assert!(cfg.enable_admin_tools);
+        assert_eq!(
+            cfg.remote_read_url.as_deref(),
+            Some("http://example:9090/api/v1/read")
+        );
+        assert_eq!(cfg.tenant_id.as_deref(), Some("team-a|team-b"));
+        assert_eq!(cfg.tenant_header, "THANOS-TENANT");
+        assert_eq!(cfg.allowed_tenants, vec!["team-a", "team-b", "team-c"]);
+        assert_eq!(
+            cfg.scrape_allowed_hosts,
+            vec!["node-exporter:9100", ".monitoring.svc"]
+        );
+        assert_eq!(cfg.flavor, BackendFlavor::Thanos);
+        assert!("victoria".parse::<BackendFlavor>().is_err());
+        vars.insert("PROMETHEUS_FLAVOR", "thanoss");
+        let err = PrometheusConfig::from_map(&vars).unwrap_err();
+        assert!(
+            err.to_string().contains("unknown backend flavor 'thanoss'"),
+            "{}",
+            err
+        );
+        vars.insert("PROMETHEUS_FLAVOR", "thanos");
+
+        // Invalid and reserved headers fail loading instead of being dropped
+        for headers in ["X-Team=observability\nAuthorization=x", "X-Team"] {
+            vars.insert("PROMETHEUS_HEADERS", headers);
+            let err = PrometheusConfig::from_map(&vars).unwrap_err();
+            assert!(err.to_string().contains("PROMETHEUS_HEADERS"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_parse_http_header() {
+        let header: HttpHeader = "X-Token = file:/run/secrets/token".parse().unwrap();
+        assert_eq!(header.name, "X-Token");
+        assert_eq!(
+            header.source,
+            HeaderSource::File("/run/secrets/token".into())
+        );
+        assert!("X-Token".parse::<HttpHeader>().is_err());
+        assert!("Bad Name=x".parse::<HttpHeader>().is_err());
+        assert!("host=example".parse::<HttpHeader>().is_err());
+        assert!("X-Token=a\nb".parse::<HttpHeader>().is_err());
     }
 }
@@ -0,0 +1,971 @@
+//! Prometheus remote-read client.
+//!
+//! Speaks the remote-read protocol (`/api/v1/read`): a snappy-compressed protobuf
+//! `ReadRequest` is posted and the server answers either with a snappy-compressed
+//! `ReadResponse` of raw samples or, when it supports it, a stream of
+//! `ChunkedReadResponse` frames carrying XOR-encoded chunks. Both are decoded into
+//! [`RawSeries`] so callers get the stored samples without PromQL evaluation.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use prost::Message;
+use reqwest::Client;
+use serde_json::Value;
+
+use crate::mcp::auth::Authorization;
+use crate::mcp::exposition::MetricFamily;
+use crate::mcp::http_client::{self, RequestHeaders};
+use crate::mcp::matchers::{parse_selector, MatchOp};
+use crate::mcp::prometheus_client::{
+    format_sample_value, Alert, ExemplarSeries, MetricMetadata, PrometheusError,
+    PrometheusQueryResult, QueryOptions, RawSeries, RuleGroup, SeriesResult, StatusSection,
+    TargetMetadata, TsdbStatus,
+};
+use crate::mcp::prometheus_config::PrometheusConfig;
+use crate::mcp::repository::PrometheusRepository;
+use crate::mcp::scrape::MAX_BODY_BYTES;
+use crate::mcp::tenant;
+
+/// Protobuf messages from `prompb/remote.proto` and `prompb/types.proto`
+pub mod proto {
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ReadRequest {
+        #[prost(message, repeated, tag = "1")]
+        pub queries: Vec<Query>,
+        #[prost(enumeration = "ResponseType", repeated, tag = "2")]
+        pub accepted_response_types: Vec<i32>,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum ResponseType {
+        Samples = 0,
+        StreamedXorChunks = 1,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Query {
+        #[prost(int64, tag = "1")]
+        pub start_timestamp_ms: i64,
+        #[prost(int64, tag = "2")]
+        pub end_timestamp_ms: i64,
+        #[prost(message, repeated, tag = "3")]
+        pub matchers: Vec<LabelMatcher>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct LabelMatcher {
+        #[prost(enumeration = "MatcherType", tag = "1")]
+        pub r#type: i32,
+        #[prost(string, tag = "2")]
+        pub name: String,
+        #[prost(string, tag = "3")]
+        pub value: String,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum MatcherType {
+        Eq = 0,
+        Neq = 1,
+        Re = 2,
+        Nre = 3,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ReadResponse {
+        #[prost(message, repeated, tag = "1")]
+        pub results: Vec<QueryResult>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct QueryResult {
+        #[prost(message, repeated, tag = "1")]
+        pub timeseries: Vec<TimeSeries>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct TimeSeries {
+        #[prost(message, repeated, tag = "1")]
+        pub labels: Vec<Label>,
+        #[prost(message, repeated, tag = "2")]
+        pub samples: Vec<Sample>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Label {
+        #[prost(string, tag = "1")]
+        pub name: String,
+        #[prost(string, tag = "2")]
+        pub value: String,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Sample {
+        #[prost(double, tag = "1")]
+        pub value: f64,
+        #[prost(int64, tag = "2")]
+        pub timestamp: i64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ChunkedReadResponse {
+        #[prost(message, repeated, tag = "1")]
+        pub chunked_series: Vec<ChunkedSeries>,
+        #[prost(int64, tag = "2")]
+        pub query_index: i64,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct ChunkedSeries {
+        #[prost(message, repeated, tag = "1")]
+        pub labels: Vec<Label>,
+        #[prost(message, repeated, tag = "2")]
+        pub chunks: Vec<Chunk>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    pub struct Chunk {
+        #[prost(int64, tag = "1")]
+        pub min_time_ms: i64,
+        #[prost(int64, tag = "2")]
+        pub max_time_ms: i64,
+        #[prost(enumeration = "ChunkEncoding", tag = "3")]
+        pub r#type: i32,
+        #[prost(bytes = "vec", tag = "4")]
+        pub data: Vec<u8>,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+    #[repr(i32)]
+    pub enum ChunkEncoding {
+        Unknown = 0,
+        Xor = 1,
+        Histogram = 2,
+        FloatHistogram = 3,
+    }
+}
+
+const STREAMED_CONTENT_TYPE: &str = "application/x-streamed-protobuf";
+/// Characters of an error response body quoted in the returned error
+const MAX_ERROR_CHARS: usize = 512;
+
+/// Client for a remote-read endpoint such as `http://prometheus:9090/api/v1/read`
+pub struct RemoteReadClient {
+    config: PrometheusConfig,
+    url: String,
+    client: Client,
+    auth: Authorization,
+    headers: RequestHeaders,
+}
+
+impl RemoteReadClient {
+    /// Create a client for `url`, reusing the HTTP settings and authorization from `config`
+    pub fn new(config: PrometheusConfig, url: &str) -> Result<Self, PrometheusError> {
+        let (client, url) = http_client::build_client(&config, url)?;
+        let auth = Authorization::from_config(&config)?;
+        let headers = RequestHeaders::from_config(&config)?;
+        Ok(Self {
+            config,
+            url,
+            client,
+            auth,
+            headers,
+        })
+    }
+
+    /// Read raw samples of all series matching `selector` in `[start_ms, end_ms]`
+    pub async fn read(
+        &self,
+        selector: &str,
+        start_ms: i64,
+        end_ms: i64,
+    ) -> Result<Vec<RawSeries>, PrometheusError> {
+        let matchers = parse_selector(selector).map_err(PrometheusError::BadData)?;
+        let request = proto::ReadRequest {
+            queries: vec![proto::Query {
+                start_timestamp_ms: start_ms,
+                end_timestamp_ms: end_ms,
+                matchers: matchers
+                    .iter()
+                    .map(|m| proto::LabelMatcher {
+                        r#type: matcher_type(m.op) as i32,
+                        name: m.name.clone(),
+                        value: m.value.clone(),
+                    })
+                    .collect(),
+            }],
+            accepted_response_types: vec![
+                proto::ResponseType::StreamedXorChunks as i32,
+                proto::ResponseType::Samples as i32,
+            ],
+        };
+        let body = snap::raw::Encoder::new()
+            .compress_vec(&request.encode_to_vec())
+            .map_err(|e| PrometheusError::Internal(format!("snappy encode: {}", e)))?;
+
+        let rb = self
+            .auth
+            .apply(self.headers.apply(self.client.post(&self.url)))
+            .header("Content-Encoding", "snappy")
+            .header("Content-Type", "application/x-protobuf")
+            .header("X-Prometheus-Remote-Read-Version", "0.1.0")
+            .body(body);
+
+        let mut response = tenant::apply_tenant(rb, &self.config).send().await?;
+        let status = response.status();
+        let streamed = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with(STREAMED_CONTENT_TYPE));
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if bytes.len() + chunk.len() > MAX_BODY_BYTES {
+                return Err(PrometheusError::ApiError(format!(
+                    "Remote read returned more than {} bytes",
+                    MAX_BODY_BYTES
+                )));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        if !status.is_success() {
+            let text = String::from_utf8_lossy(&bytes);
+            let text = text.trim();
+            let mut message: String = text.chars().take(MAX_ERROR_CHARS).collect();
+            if message.len() < text.len() {
+                message.push_str("...");
+            }
+            return Err(PrometheusError::ApiError(format!(
+                "Remote read returned {}: {}",
+                status, message
+            )));
+        }
+
+        if streamed {
+            decode_streamed_response(&bytes, start_ms, end_ms)
+        } else {
+            decode_samples_response(&bytes)
+        }
+    }
+}
+
+fn matcher_type(op: MatchOp) -> proto::MatcherType {
+    match op {
+        MatchOp::Equal => proto::MatcherType::Eq,
+        MatchOp::NotEqual => proto::MatcherType::Neq,
+        MatchOp::RegexMatch => proto::MatcherType::Re,
+        MatchOp::RegexNoMatch => proto::MatcherType::Nre,
+    }
+}
+
+fn parse_error(msg: impl std::fmt::Display) -> PrometheusError {
+    PrometheusError::ParseError(format!("Failed to parse remote read response: {}", msg))
+}
+
+fn label_map(labels: &[proto::Label]) -> HashMap<String, String> {
+    labels
+        .iter()
+        .map(|l| (l.name.clone(), l.value.clone()))
+        .collect()
+}
+
+/// Decode a snappy-compressed `ReadResponse` (the `SAMPLES` response type)
+fn decode_samples_response(bytes: &[u8]) -> Result<Vec<RawSeries>, PrometheusError> {
+    let raw = snap::raw::Decoder::new()
+        .decompress_vec(bytes)
+        .map_err(parse_error)?;
+    let response = proto::ReadResponse::decode(raw.as_slice()).map_err(parse_error)?;
+    Ok(response
+        .results
+        .into_iter()
+        .flat_map(|r| r.timeseries)
+        .map(|ts| RawSeries {
+            labels: label_map(&ts.labels),
+            samples: ts
+                .samples
+                .iter()
+                .map(|s| (s.timestamp, format_sample_value(s.value)))
+                .collect(),
+        })
+        .collect())
+}
+
+/// Decode a stream of `ChunkedReadResponse` frames (the `STREAMED_XOR_CHUNKS` response type).
+///
+/// Each frame is `uvarint(len) | crc32c(data) as big-endian u32 | data`. A series may be
+/// split over consecutive frames, in which case its chunks are appended to the previous entry.
+fn decode_streamed_response(
+    mut bytes: &[u8],
+    start_ms: i64,
+    end_ms: i64,
+) -> Result<Vec<RawSeries>, PrometheusError> {
+    let mut out: Vec<RawSeries> = Vec::new();
+    while !bytes.is_empty() {
+        let (len, n) = read_uvarint(bytes).ok_or_else(|| parse_error("truncated frame size"))?;
+        bytes = &bytes[n..];
+        // The size comes from the server; checksum (4 bytes) and data must both fit
+        let frame_end = usize::try_from(len)
+            .ok()
+            .and_then(|len| len.checked_add(4))
+            .filter(|&end| end <= bytes.len())
+            .ok_or_else(|| parse_error("truncated frame"))?;
+        let checksum = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        let data = &bytes[4..frame_end];
+        if crc32c::crc32c(data) != checksum {
+            return Err(parse_error("frame checksum mismatch"));
+        }
+        bytes = &bytes[frame_end..];
+
+        let frame = proto::ChunkedReadResponse::decode(data).map_err(parse_error)?;
+        for series in frame.chunked_series {
+            let labels = label_map(&series.labels);
+            let mut samples = Vec::new();
+            for chunk in &series.chunks {
+                if chunk.r#type != proto::ChunkEncoding::Xor as i32 {
+                    return Err(PrometheusError::Unsupported(format!(
+                        "remote read chunk encoding {} is not supported",
+                        chunk.r#type
+                    )));
+                }
+                for (t, v) in decode_xor_chunk(&chunk.data)? {
+                    if t >= start_ms && t <= end_ms {
+                        samples.push((t, format_sample_value(v)));
+                    }
+                }
+            }
+            match out.last_mut() {
+                Some(last) if last.labels == labels => last.samples.extend(samples),
+                _ => out.push(RawSeries { labels, samples }),
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// Read a protobuf/Go-style unsigned varint, returning the value and bytes consumed
+fn read_uvarint(bytes: &[u8]) -> Option<(u64, usize)> {
+    let mut value = 0u64;
+    for (i, b) in bytes.iter().enumerate().take(10) {
+        value |= u64::from(b & 0x7f) << (7 * i);
+        if b & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+/// Big-endian bit reader over a chunk, matching the TSDB `bstream` layout
+struct BitReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl BitReader<'_> {
+    fn read_bit(&mut self) -> Option<bool> {
+        let byte = *self.data.get(self.pos / 8)?;
+        let bit = byte & (0x80 >> (self.pos % 8)) != 0;
+        self.pos += 1;
+        Some(bit)
+    }
+
+    fn read_bits(&mut self, n: u32) -> Option<u64> {
+        let mut v = 0u64;
+        for _ in 0..n {
+            v = (v << 1) | u64::from(self.read_bit()?);
+        }
+        Some(v)
+    }
+
+    fn read_uvarint(&mut self) -> Option<u64> {
+        let mut value = 0u64;
+        for i in 0..10 {
+            let b = self.read_bits(8)?;
+            value |= (b & 0x7f) << (7 * i);
+            if b & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    fn read_varint(&mut self) -> Option<i64> {
+        let u = self.read_uvarint()?;
+        Some(((u >> 1) as i64) ^ -((u & 1) as i64))
+    }
+}
+
+/// Decode a Gorilla-style XOR chunk into `(timestamp_ms, value)` pairs
+fn decode_xor_chunk(data: &[u8]) -> Result<Vec<(i64, f64)>, PrometheusError> {
+    let truncated = || parse_error("truncated XOR chunk");
+    if data.len() < 2 {
+        return Err(truncated());
+    }
+    let count = u16::from_be_bytes([data[0], data[1]]) as usize;
+    let mut r = BitReader {
+        data: &data[2..],
+        pos: 0,
+    };
+    let mut out = Vec::with_capacity(count);
+    let (mut t, mut t_delta) = (0i64, 0i64);
+    let mut v_bits = 0u64;
+    let (mut leading, mut trailing) = (0u32, 0u32);
+
+    for i in 0..count {
+        match i {
+            0 => {
+                t = r.read_varint().ok_or_else(truncated)?;
+                v_bits = r.read_bits(64).ok_or_else(truncated)?;
+            }
+            _ => {
+                if i == 1 {
+                    t_delta = r.read_uvarint().ok_or_else(truncated)? as i64;
+                } else {
+                    let mut prefix = 0;
+                    while prefix < 4 && r.read_bit().ok_or_else(truncated)? {
+                        prefix += 1;
+                    }
+                    let size = match prefix {
+                        0 => 0,
+                        1 => 14,
+                        2 => 17,
+                        3 => 20,
+                        _ => 64,
+                    };
+                    let mut dod = r.read_bits(size).ok_or_else(truncated)? as i64;
+                    if size > 0 && size < 64 && dod > 1 << (size - 1) {
+                        dod -= 1 << size;
+                    }
+                    t_delta = t_delta.wrapping_add(dod);
+                }
+                t = t.wrapping_add(t_delta);
+
+                if r.read_bit().ok_or_else(truncated)? {
+                    if r.read_bit().ok_or_else(truncated)? {
+                        leading = r.read_bits(5).ok_or_else(truncated)? as u32;
+                        let mut sig = r.read_bits(6).ok_or_else(truncated)? as u32;
+                        if sig == 0 {
+                            sig = 64;
+                        }
+                        trailing = 64u32
+                            .checked_sub(leading + sig)
+                            .ok_or_else(|| parse_error("invalid XOR chunk value header"))?;
+                    }
+                    let sig = 64 - leading - trailing;
+                    let bits = r.read_bits(sig).ok_or_else(truncated)?;
+                    v_bits ^= bits << trailing;
+                }
+            }
+        }
+        out.push((t, f64::from_bits(v_bits)));
+    }
+    Ok(out)
+}
+
+/// Repository that serves `read_raw` over remote read and delegates everything else
+pub struct RemoteReadRepository {
+    client: RemoteReadClient,
+    inner: Arc<dyn PrometheusRepository>,
+}
+
+impl RemoteReadRepository {
+    pub fn new(
+        config: PrometheusConfig,
+        url: &str,
+        inner: Arc<dyn PrometheusRepository>,
+    ) -> Result<Self, PrometheusError> {
+        Ok(Self {
+            client: RemoteReadClient::new(config, url)?,
+            inner,
+        })
+    }
+}
+
+#[async_trait]
+impl PrometheusRepository for RemoteReadRepository {
+    async fn query(
+        &self,
+        query: &str,
+        time: Option<&str>,
+        options: &QueryOptions,
+    ) -> Result<PrometheusQueryResult, PrometheusError> {
+        self.inner.query(query, time, options).await
+    }
+
+    async fn query_range(
+        &self,
+        query: &str,
+        start: &str,
+        end: &str,
+        step: &str,
+        options: &QueryOptions,
+    ) -> Result<PrometheusQueryResult, PrometheusError> {
+        self.inner
+            .query_range(query, start, end, step, options)
+            .await
+    }
+
+    async fn list_metrics(&self) -> Result<Vec<String>, PrometheusError> {
+        self.inner.list_metrics().await
+    }
+
+    async fn get_metadata(
+        &self,
+        metric: Option<&str>,
+        limit: Option<u64>,
+        limit_per_metric: Option<u64>,
+    ) -> Result<Vec<MetricMetadata>, PrometheusError> {
+        self.inner
+            .get_metadata(metric, limit, limit_per_metric)
+            .await
+    }
+
+    async fn get_targets_metadata(
+        &self,
+        match_target: Option<&str>,
+        metric: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<Vec<TargetMetadata>, PrometheusError> {
+        self.inner
+            .get_targets_metadata(match_target, metric, limit)
+            .await
+    }
+
+    async fn get_series(
+        &self,
+        match_strings: Vec<&str>,
+        start: Option<&str>,
+        end: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<SeriesResult, PrometheusError> {
+        self.inner
+            .get_series(match_strings, start, end, limit)
+            .await
+    }
+
+    async fn get_label_values(
+        &self,
+        label_name: &str,
+        match_strings: Vec<&str>,
+        start: Option<&str>,
+        end: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<Vec<String>, PrometheusError> {
+        self.inner
+            .get_label_values(label_name, match_strings, start, end, limit)
+            .await
+    }
+
+    async fn list_labels(
+        &self,
+        match_strings: Vec<&str>,
+        start: Option<&str>,
+        end: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<Vec<String>, PrometheusError> {
+        self.inner
+            .list_labels(match_strings, start, end, limit)
+            .await
+    }
+
+    async fn get_alerts(&self) -> Result<Vec<Alert>, PrometheusError> {
+        self.inner.get_alerts().await
+    }
+
+    async fn get_rules(
+        &self,
+        rule_type: Option<&str>,
+        rule_names: Vec<&str>,
+        rule_groups: Vec<&str>,
+        files: Vec<&str>,
+    ) -> Result<Vec<RuleGroup>, PrometheusError> {
+        self.inner
+            .get_rules(rule_type, rule_names, rule_groups, files)
+            .await
+    }
+
+    async fn get_status(&self, section: StatusSection) -> Result<Value, PrometheusError> {
+        self.inner.get_status(section).await
+    }
+
+    async fn get_tsdb_status(&self, limit: Option<u64>) -> Result<TsdbStatus, PrometheusError> {
+        self.inner.get_tsdb_status(limit).await
+    }
+
+    async fn query_exemplars(
+        &self,
+        query: &str,
+        start: Option<&str>,
+        end: Option<&str>,
+    ) -> Result<Vec<ExemplarSeries>, PrometheusError> {
+        self.inner.query_exemplars(query, start, end).await
+    }
+
+    async fn format_query(&self, query: &str) -> Result<String, PrometheusError> {
+        self.inner.format_query(query).await
+    }
+
+    async fn parse_query(&self, query: &str) -> Result<Value, PrometheusError> {
+        self.inner.parse_query(query).await
+    }
+
+    async fn snapshot(&self, skip_head: bool) -> Result<String, PrometheusError> {
+        self.inner.snapshot(skip_head).await
+    }
+
+    async fn delete_series(
+        &self,
+        match_strings: Vec<&str>,
+        start: Option<&str>,
+        end: Option<&str>,
+    ) -> Result<(), PrometheusError> {
+        self.inner.delete_series(match_strings, start, end).await
+    }
+
+    async fn clean_tombstones(&self) -> Result<(), PrometheusError> {
+        self.inner.clean_tombstones().await
+    }
+
+    async fn read_raw(
+        &self,
+        selector: &str,
+        start_ms: i64,
+        end_ms: i64,
+    ) -> Result<Vec<RawSeries>, PrometheusError> {
+        self.client.read(selector, start_ms, end_ms).await
+    }
+
+    async fn federate(
+        &self,
+        match_strings: Vec<&str>,
+    ) -> Result<Vec<MetricFamily>, PrometheusError> {
+        self.inner.federate(match_strings).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::test_server::{serve, serve_once, Request, Response};
+
+    /// Test-only XOR chunk encoder mirroring the TSDB `xorAppender`
+    fn encode_xor_chunk(samples: &[(i64, f64)]) -> Vec<u8> {
+        fn push(bits: &mut Vec<bool>, v: u64, n: u32) {
+            for i in (0..n).rev() {
+                bits.push((v >> i) & 1 == 1);
+            }
+        }
+        fn push_uvarint(bits: &mut Vec<bool>, mut v: u64) {
+            while v >= 0x80 {
+                push(bits, (v & 0x7f) | 0x80, 8);
+                v >>= 7;
+            }
+            push(bits, v, 8);
+        }
+
+        let mut bits = Vec::new();
+        let (mut t_delta, mut leading, mut trailing) = (0i64, 0xffu32, 0u32);
+        for (i, &(t, v)) in samples.iter().enumerate() {
+            if i == 0 {
+                push_uvarint(&mut bits, ((t << 1) ^ (t >> 63)) as u64);
+                push(&mut bits, v.to_bits(), 64);
+                continue;
+            }
+            let (prev_t, prev_v) = samples[i - 1];
+            if i == 1 {
+                t_delta = t - prev_t;
+                push_uvarint(&mut bits, t_delta as u64);
+            } else {
+                let delta = t - prev_t;
+                let dod = delta - t_delta;
+                t_delta = delta;
+                let fits = |n: u32| -((1i64 << (n - 1)) - 1) <= dod && dod <= 1 << (n - 1);
+                let (prefix, prefix_len, size) = match dod {
+                    0 => (0, 1, 0),
+                    _ if fits(14) => (0b10, 2, 14),
+                    _ if fits(17) => (0b110, 3, 17),
+                    _ if fits(20) => (0b1110, 4, 20),
+                    _ => (0b1111, 4, 64),
+                };
+                push(&mut bits, prefix, prefix_len);
+                let mask = if size == 64 {
+                    u64::MAX
+                } else {
+                    (1 << size) - 1
+                };
+                push(&mut bits, dod as u64 & mask, size);
+            }
+
+            let delta = v.to_bits() ^ prev_v.to_bits();
+            if delta == 0 {
+                push(&mut bits, 0, 1);
+                continue;
+            }
+            push(&mut bits, 1, 1);
+            let new_leading = delta.leading_zeros().min(31);
+            let new_trailing = delta.trailing_zeros();
+            if leading != 0xff && new_leading >= leading && new_trailing >= trailing {
+                push(&mut bits, 0, 1);
+                push(&mut bits, delta >> trailing, 64 - leading - trailing);
+            } else {
+                leading = new_leading;
+                trailing = new_trailing;
+                let sig = 64 - leading - trailing;
+                push(&mut bits, 1, 1);
+                push(&mut bits, u64::from(leading), 5);
+                push(&mut bits, u64::from(sig & 63), 6);
+                push(&mut bits, delta >> trailing, sig);
+            }
+        }
+
+        let mut out = (samples.len() as u16).to_be_bytes().to_vec();
+        for byte in bits.chunks(8) {
+            let b = byte
+                .iter()
+                .enumerate()
+                .fold(0u8, |acc, (i, &bit)| acc | (u8::from(bit) << (7 - i)));
+            out.push(b);
+        }
+        out
+    }
+
+    fn test_samples() -> Vec<(i64, f64)> {
+        let t0 = 1_700_000_000_000;
+        // Gaps chosen to hit every delta-of-delta width, including a negative one
+        let offsets = [
+            0, 15_000, 30_000, 45_001, 75_000, 175_000, 10_175_000, 10_189_000,
+        ];
+        let values = [1.0, 1.0, 2.5, 0.1, f64::NAN, -3.25, 1e300, f64::INFINITY];
+        offsets
+            .iter()
+            .zip(values)
+            .map(|(o, v)| (t0 + o, v))
+            .collect()
+    }
+
+    #[test]
+    fn test_decode_xor_chunk_roundtrip() {
+        let samples = test_samples();
+        let decoded = decode_xor_chunk(&encode_xor_chunk(&samples)).unwrap();
+        assert_eq!(decoded.len(), samples.len());
+        for ((t, v), (et, ev)) in decoded.iter().zip(&samples) {
+            assert_eq!(t, et);
+            assert_eq!(v.to_bits(), ev.to_bits());
+        }
+        assert!(decode_xor_chunk(&encode_xor_chunk(&samples)[..6]).is_err());
+    }
+
+    #[test]
+    fn test_decode_xor_chunk_rejects_bad_value_header() {
+        let mut bits = Vec::new();
+        let mut push = |v: u64, n: u32| {
+            for i in (0..n).rev() {
+                bits.push((v >> i) & 1 == 1);
+            }
+        };
+        // Two samples: t=0 (zigzag varint), value 1.0, then t_delta=1
+        push(0, 8);
+        push(1f64.to_bits(), 64);
+        push(1, 8);
+        // New window with 31 leading and 63 significant bits: more than 64 in total
+        push(0b11, 2);
+        push(31, 5);
+        push(63, 6);
+        push(0, 64);
+        let mut chunk = 2u16.to_be_bytes().to_vec();
+        chunk.extend(bits.chunks(8).map(|byte| {
+            byte.iter()
+                .enumerate()
+                .fold(0u8, |acc, (i, &bit)| acc | (u8::from(bit) << (7 - i)))
+        }));
+        assert!(decode_xor_chunk(&chunk)
+            .unwrap_err()
+            .to_string()
+            .contains("invalid XOR chunk value header"));
+    }
+
+    #[test]
+    fn test_decode_streamed_response_rejects_bad_frames() {
+        let frame = |data: &[u8]| {
+            let mut out = vec![data.len() as u8];
+            out.extend(crc32c::crc32c(data).to_be_bytes());
+            out.extend(data);
+            out
+        };
+        assert!(decode_streamed_response(&frame(&[]), 0, 1)
+            .unwrap()
+            .is_empty());
+
+        // Frame size larger than the remaining bytes
+        let mut truncated = frame(&[0x0a, 0x00]);
+        truncated.pop();
+        assert!(decode_streamed_response(&truncated, 0, 1).is_err());
+        // Frame size near u64::MAX must not overflow
+        let mut oversized = vec![0xff; 9];
+        oversized.extend([0x01, 0, 0, 0, 0]);
+        assert!(decode_streamed_response(&oversized, 0, 1).is_err());
+        // Frame size varint cut off
+        assert!(decode_streamed_response(&[0x80], 0, 1).is_err());
+        // Checksum only, no room for it
+        assert!(decode_streamed_response(&[0x00, 0x01, 0x02], 0, 1).is_err());
+    }
+
+    fn labels(pairs: &[(&str, &str)]) -> Vec<proto::Label> {
+        pairs
+            .iter()
+            .map(|(n, v)| proto::Label {
+                name: n.to_string(),
+                value: v.to_string(),
+            })
+            .collect()
+    }
+
+    fn frame(msg: &proto::ChunkedReadResponse) -> Vec<u8> {
+        let data = msg.encode_to_vec();
+        let mut out = Vec::new();
+        let mut len = data.len() as u64;
+        while len >= 0x80 {
+            out.push((len as u8) | 0x80);
+            len >>= 7;
+        }
+        out.push(len as u8);
+        out.extend(crc32c::crc32c(&data).to_be_bytes());
+        out.extend(data);
+        out
+    }
+
+    /// Stub remote-read endpoint: `/samples` answers with a `ReadResponse`, `/stream` with
+    /// chunked frames
+    fn respond(request: &Request) -> Response {
+        let samples = test_samples();
+        let (content_type, body) = if request.head.starts_with("POST /stream") {
+            let series = |chunk: &[(i64, f64)]| proto::ChunkedSeries {
+                labels: labels(&[("__name__", "up"), ("job", "node")]),
+                chunks: vec![proto::Chunk {
+                    min_time_ms: chunk[0].0,
+                    max_time_ms: chunk[chunk.len() - 1].0,
+                    r#type: proto::ChunkEncoding::Xor as i32,
+                    data: encode_xor_chunk(chunk),
+                }],
+            };
+            // One series split across two frames
+            let mut body = frame(&proto::ChunkedReadResponse {
+                chunked_series: vec![series(&samples[..4])],
+                query_index: 0,
+            });
+            body.extend(frame(&proto::ChunkedReadResponse {
+                chunked_series: vec![series(&samples[4..])],
+                query_index: 0,
+            }));
+            (
+                "application/x-streamed-protobuf; proto=prometheus.ChunkedReadResponse",
+                body,
+            )
+        } else {
+            let response = proto::ReadResponse {
+                results: vec![proto::QueryResult {
+                    timeseries: vec![proto::TimeSeries {
+                        labels: labels(&[("__name__", "up"), ("job", "api")]),
+                        samples: samples
+                            .iter()
+                            .map(|&(timestamp, value)| proto::Sample { value, timestamp })
+                            .collect(),
+                    }],
+                }],
+            };
+            let body = snap::raw::Encoder::new()
+                .compress_vec(&response.encode_to_vec())
+                .unwrap();
+            ("application/x-protobuf", body)
+        };
+        Response::new("200 OK", body)
+            .header("Content-Type", content_type)
+            .header("Content-Encoding", "snappy")
+    }
+
+    fn decode_request(request: &Request) -> proto::ReadRequest {
+        let raw = snap::raw::Decoder::new()
+            .decompress_vec(&request.body)
+            .unwrap();
+        proto::ReadRequest::decode(raw.as_slice()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_remote_read_samples_and_streamed_chunks() {
+        let (url, server) = serve(2, respond).await;
+        let samples = test_samples();
+        let (start, end) = (samples[1].0, samples[6].0);
+
+        let client =
+            RemoteReadClient::new(PrometheusConfig::default(), &format!("{}/samples", url))
+                .unwrap();
+        let series = client
+            .read("up{job=~\"api|node\"}", start, end)
+            .await
+            .unwrap();
+        assert_eq!(series.len(), 1);
+        assert_eq!(series[0].labels["job"], "api");
+        assert_eq!(series[0].samples.len(), samples.len());
+        assert_eq!(series[0].samples[4].1, "NaN");
+        assert_eq!(series[0].samples[7].1, "+Inf");
+
+        let client =
+            RemoteReadClient::new(PrometheusConfig::default(), &format!("{}/stream", url)).unwrap();
+        let series = client.read("up", start, end).await.unwrap();
+        assert_eq!(series.len(), 1);
+        assert_eq!(series[0].labels["job"], "node");
+        // Chunk samples outside [start, end] are dropped
+        let expected: Vec<(i64, String)> = samples[1..=6]
+            .iter()
+            .map(|&(t, v)| (t, format_sample_value(v)))
+            .collect();
+        assert_eq!(series[0].samples, expected);
+
+        let requests: Vec<proto::ReadRequest> =
+            server.await.unwrap().iter().map(decode_request).collect();
+        let query = &requests[0].queries[0];
+        assert_eq!(
+            (query.start_timestamp_ms, query.end_timestamp_ms),
+            (start, end)
+        );
+        assert_eq!(query.matchers.len(), 2);
+        assert_eq!(query.matchers[0].name, "__name__");
+        assert_eq!(query.matchers[1].r#type, proto::MatcherType::Re as i32);
+        assert_eq!(query.matchers[1].value, "api|node");
+        assert_eq!(
+            requests[1].accepted_response_types,
+            vec![
+                proto::ResponseType::StreamedXorChunks as i32,
+                proto::ResponseType::Samples as i32
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_remote_read_limits_response_body() {
+        let client = |url: String| {
+            RemoteReadClient::new(PrometheusConfig::default(), &format!("{}/api/v1/read", url))
+                .unwrap()
+        };
+
+        let (url, server) = serve_once(Response::new(
+            "500 Internal Server Error",
+            "x".repeat(MAX_ERROR_CHARS * 4),
+        ))
+        .await;
+        let err = client(url).read("up", 0, 1000).await.unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("500"), "{}", message);
+        assert!(message.ends_with("..."), "{}", message);
+        assert!(message.len() < MAX_ERROR_CHARS + 100);
+        server.await.unwrap();
+
+        let (url, server) =
+            serve_once(Response::new("200 OK", vec![0u8; MAX_BODY_BYTES + 1])).await;
+        let err = client(url).read("up", 0, 1000).await.unwrap_err();
+        assert!(err.to_string().contains("more than"), "{}", err);
+        server.await.unwrap();
+    }
+}
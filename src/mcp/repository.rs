@@ -2,11 +2,16 @@ use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
+use serde_json::Value;
 
+use crate::mcp::exposition::MetricFamily;
 use crate::mcp::prometheus_client::{
-    MetricMetadata, PrometheusClient, PrometheusError, PrometheusQueryResult,
+    Alert, ExemplarSeries, MetricMetadata, PrometheusClient, PrometheusError,
+    PrometheusQueryResult, QueryOptions, RawSeries, RuleGroup, SeriesResult, StatusSection,
+    TargetMetadata, TsdbStatus,
 };
 use crate::mcp::prometheus_config::PrometheusConfig;
+use crate::mcp::tenant;
 
 use once_cell::sync::Lazy;
 
@@ -16,6 +21,7 @@ pub trait PrometheusRepository: Send + Sync {
         &self,
         query: &str,
         time: Option<&str>,
+        options: &QueryOptions,
     ) -> Result<PrometheusQueryResult, PrometheusError>;
     async fn query_range(
         &self,
@@ -23,21 +29,89 @@ pub trait PrometheusRepository: Send + Sync {
         start: &str,
         end: &str,
         step: &str,
+        options: &QueryOptions,
     ) -> Result<PrometheusQueryResult, PrometheusError>;
     async fn list_metrics(&self) -> Result<Vec<String>, PrometheusError>;
-    async fn get_metadata(&self, metric: &str) -> Result<Vec<MetricMetadata>, PrometheusError>;
+    async fn get_metadata(
+        &self,
+        metric: Option<&str>,
+        limit: Option<u64>,
+        limit_per_metric: Option<u64>,
+    ) -> Result<Vec<MetricMetadata>, PrometheusError>;
+    async fn get_targets_metadata(
+        &self,
+        match_target: Option<&str>,
+        metric: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<Vec<TargetMetadata>, PrometheusError>;
     async fn get_series(
         &self,
         match_strings: Vec<&str>,
-    ) -> Result<Vec<std::collections::HashMap<String, String>>, PrometheusError>;
-    async fn get_label_values(&self, label_name: &str) -> Result<Vec<String>, PrometheusError>;
+        start: Option<&str>,
+        end: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<SeriesResult, PrometheusError>;
+    async fn get_label_values(
+        &self,
+        label_name: &str,
+        match_strings: Vec<&str>,
+        start: Option<&str>,
+        end: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<Vec<String>, PrometheusError>;
+    async fn list_labels(
+        &self,
+        match_strings: Vec<&str>,
+        start: Option<&str>,
+        end: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<Vec<String>, PrometheusError>;
+    async fn get_alerts(&self) -> Result<Vec<Alert>, PrometheusError>;
+    async fn get_rules(
+        &self,
+        rule_type: Option<&str>,
+        rule_names: Vec<&str>,
+        rule_groups: Vec<&str>,
+        files: Vec<&str>,
+    ) -> Result<Vec<RuleGroup>, PrometheusError>;
+    async fn get_status(&self, section: StatusSection) -> Result<Value, PrometheusError>;
+    async fn get_tsdb_status(&self, limit: Option<u64>) -> Result<TsdbStatus, PrometheusError>;
+    async fn query_exemplars(
+        &self,
+        query: &str,
+        start: Option<&str>,
+        end: Option<&str>,
+    ) -> Result<Vec<ExemplarSeries>, PrometheusError>;
+    async fn format_query(&self, query: &str) -> Result<String, PrometheusError>;
+    async fn parse_query(&self, query: &str) -> Result<Value, PrometheusError>;
+    async fn snapshot(&self, skip_head: bool) -> Result<String, PrometheusError>;
+    async fn delete_series(
+        &self,
+        match_strings: Vec<&str>,
+        start: Option<&str>,
+        end: Option<&str>,
+    ) -> Result<(), PrometheusError>;
+    async fn clean_tombstones(&self) -> Result<(), PrometheusError>;
+    async fn read_raw(
+        &self,
+        selector: &str,
+        start_ms: i64,
+        end_ms: i64,
+    ) -> Result<Vec<RawSeries>, PrometheusError>;
+    async fn federate(
+        &self,
+        match_strings: Vec<&str>,
+    ) -> Result<Vec<MetricFamily>, PrometheusError>;
 }
 
+/// Cached name lists with the time they were fetched
+type Cache<K> = std::collections::HashMap<K, (Instant, Vec<String>)>;
+
 pub struct HttpPrometheusRepository {
     client: PrometheusClient,
-    // Simple caches
-    metrics_cache: RwLock<Option<(Instant, Vec<String>)>>, // cache for list_metrics
-    labels_cache: RwLock<std::collections::HashMap<String, (Instant, Vec<String>)>>, // per-label cache
+    // Simple caches, keyed by the tenant selected for the call ("" for the configured one)
+    metrics_cache: RwLock<Cache<String>>, // cache for list_metrics
+    labels_cache: RwLock<Cache<(String, String)>>, // per-label cache
     cache_ttl: Duration,
 }
 
@@ -50,14 +124,14 @@ impl HttpPrometheusRepository {
             .unwrap_or_else(|| Duration::from_secs(0));
         Ok(Self {
             client,
-            metrics_cache: RwLock::new(None),
+            metrics_cache: RwLock::new(std::collections::HashMap::new()),
             labels_cache: RwLock::new(std::collections::HashMap::new()),
             cache_ttl: ttl,
         })
     }
 
     pub fn from_env() -> Result<Self, PrometheusError> {
-        Self::new(PrometheusConfig::from_env())
+        Self::new(PrometheusConfig::from_env()?)
     }
 
     fn is_expired(ts: Instant, ttl: Duration) -> bool {
@@ -71,8 +145,9 @@ impl PrometheusRepository for HttpPrometheusRepository {
         &self,
         query: &str,
         time: Option<&str>,
+        options: &QueryOptions,
     ) -> Result<PrometheusQueryResult, PrometheusError> {
-        self.client.query(query, time).await
+        self.client.query(query, time, options).await
     }
 
     async fn query_range(
@@ -81,14 +156,18 @@ impl PrometheusRepository for HttpPrometheusRepository {
         start: &str,
         end: &str,
         step: &str,
+        options: &QueryOptions,
     ) -> Result<PrometheusQueryResult, PrometheusError> {
-        self.client.query_range(query, start, end, step).await
+        self.client
+            .query_range(query, start, end, step, options)
+            .await
     }
 
     async fn list_metrics(&self) -> Result<Vec<String>, PrometheusError> {
         // Try cache
+        let tenant = tenant::call_tenant().unwrap_or_default();
         if self.cache_ttl > Duration::from_secs(0) {
-            if let Some((ts, cached)) = self.metrics_cache.read().unwrap().as_ref() {
+            if let Some((ts, cached)) = self.metrics_cache.read().unwrap().get(&tenant) {
                 if !Self::is_expired(*ts, self.cache_ttl) {
                     return Ok(cached.clone());
                 }
@@ -96,39 +175,171 @@ impl PrometheusRepository for HttpPrometheusRepository {
         }
         let fresh = self.client.list_metrics().await?;
         if self.cache_ttl > Duration::from_secs(0) {
-            *self.metrics_cache.write().unwrap() = Some((Instant::now(), fresh.clone()));
+            self.metrics_cache
+                .write()
+                .unwrap()
+                .insert(tenant, (Instant::now(), fresh.clone()));
         }
         Ok(fresh)
     }
 
-    async fn get_metadata(&self, metric: &str) -> Result<Vec<MetricMetadata>, PrometheusError> {
-        self.client.get_metadata(metric).await
+    async fn get_metadata(
+        &self,
+        metric: Option<&str>,
+        limit: Option<u64>,
+        limit_per_metric: Option<u64>,
+    ) -> Result<Vec<MetricMetadata>, PrometheusError> {
+        self.client
+            .get_metadata(metric, limit, limit_per_metric)
+            .await
+    }
+
+    async fn get_targets_metadata(
+        &self,
+        match_target: Option<&str>,
+        metric: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<Vec<TargetMetadata>, PrometheusError> {
+        self.client
+            .get_targets_metadata(match_target, metric, limit)
+            .await
     }
 
     async fn get_series(
         &self,
         match_strings: Vec<&str>,
-    ) -> Result<Vec<std::collections::HashMap<String, String>>, PrometheusError> {
-        self.client.get_series(match_strings).await
+        start: Option<&str>,
+        end: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<SeriesResult, PrometheusError> {
+        self.client
+            .get_series(match_strings, start, end, limit)
+            .await
     }
 
-    async fn get_label_values(&self, label_name: &str) -> Result<Vec<String>, PrometheusError> {
-        if self.cache_ttl > Duration::from_secs(0) {
-            if let Some((ts, cached)) = self.labels_cache.read().unwrap().get(label_name) {
+    async fn get_label_values(
+        &self,
+        label_name: &str,
+        match_strings: Vec<&str>,
+        start: Option<&str>,
+        end: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<Vec<String>, PrometheusError> {
+        // Only the unfiltered listing is cached
+        let cacheable = self.cache_ttl > Duration::from_secs(0)
+            && match_strings.is_empty()
+            && start.is_none()
+            && end.is_none()
+            && limit.is_none();
+        let key = (
+            tenant::call_tenant().unwrap_or_default(),
+            label_name.to_string(),
+        );
+        if cacheable {
+            if let Some((ts, cached)) = self.labels_cache.read().unwrap().get(&key) {
                 if !Self::is_expired(*ts, self.cache_ttl) {
                     return Ok(cached.clone());
                 }
             }
         }
-        let fresh = self.client.get_label_values(label_name).await?;
-        if self.cache_ttl > Duration::from_secs(0) {
+        let fresh = self
+            .client
+            .get_label_values(label_name, match_strings, start, end, limit)
+            .await?;
+        if cacheable {
             self.labels_cache
                 .write()
                 .unwrap()
-                .insert(label_name.to_string(), (Instant::now(), fresh.clone()));
+                .insert(key, (Instant::now(), fresh.clone()));
         }
         Ok(fresh)
     }
+
+    async fn list_labels(
+        &self,
+        match_strings: Vec<&str>,
+        start: Option<&str>,
+        end: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<Vec<String>, PrometheusError> {
+        self.client
+            .list_labels(match_strings, start, end, limit)
+            .await
+    }
+
+    async fn get_alerts(&self) -> Result<Vec<Alert>, PrometheusError> {
+        self.client.get_alerts().await
+    }
+
+    async fn get_rules(
+        &self,
+        rule_type: Option<&str>,
+        rule_names: Vec<&str>,
+        rule_groups: Vec<&str>,
+        files: Vec<&str>,
+    ) -> Result<Vec<RuleGroup>, PrometheusError> {
+        self.client
+            .get_rules(rule_type, rule_names, rule_groups, files)
+            .await
+    }
+
+    async fn get_status(&self, section: StatusSection) -> Result<Value, PrometheusError> {
+        self.client.get_status(section).await
+    }
+
+    async fn get_tsdb_status(&self, limit: Option<u64>) -> Result<TsdbStatus, PrometheusError> {
+        self.client.get_tsdb_status(limit).await
+    }
+
+    async fn query_exemplars(
+        &self,
+        query: &str,
+        start: Option<&str>,
+        end: Option<&str>,
+    ) -> Result<Vec<ExemplarSeries>, PrometheusError> {
+        self.client.query_exemplars(query, start, end).await
+    }
+
+    async fn format_query(&self, query: &str) -> Result<String, PrometheusError> {
+        self.client.format_query(query).await
+    }
+
+    async fn parse_query(&self, query: &str) -> Result<Value, PrometheusError> {
+        self.client.parse_query(query).await
+    }
+
+    async fn snapshot(&self, skip_head: bool) -> Result<String, PrometheusError> {
+        self.client.snapshot(skip_head).await
+    }
+
+    async fn delete_series(
+        &self,
+        match_strings: Vec<&str>,
+        start: Option<&str>,
+        end: Option<&str>,
+    ) -> Result<(), PrometheusError> {
+        self.client.delete_series(match_strings, start, end).await
+    }
+
+    async fn clean_tombstones(&self) -> Result<(), PrometheusError> {
+        self.client.clean_tombstones().await
+    }
+
+    async fn read_raw(
+        &self,
+        selector: &str,
+        start_ms: i64,
+        end_ms: i64,
+    ) -> Result<Vec<RawSeries>, PrometheusError> {
+        self.client.read_raw(selector, start_ms, end_ms).await
+    }
+
+    async fn federate(
+        &self,
+        match_strings: Vec<&str>,
+    ) -> Result<Vec<MetricFamily>, PrometheusError> {
+        self.client.federate(match_strings).await
+    }
 }
 
 static REPO: Lazy<RwLock<Option<Arc<dyn PrometheusRepository>>>> = Lazy::new(|| RwLock::new(None));
@@ -155,6 +366,7 @@ pub fn get_repository() -> Arc<dyn PrometheusRepository> {
                     &self,
                     _query: &str,
                     _time: Option<&str>,
+                    _options: &QueryOptions,
                 ) -> Result<PrometheusQueryResult, PrometheusError> {
                     Err(PrometheusError::ApiError(format!(
                         "Repository init error: {:?}",
@@ -167,6 +379,7 @@ pub fn get_repository() -> Arc<dyn PrometheusRepository> {
                     _start: &str,
                     _end: &str,
                     _step: &str,
+                    _options: &QueryOptions,
                 ) -> Result<PrometheusQueryResult, PrometheusError> {
                     Err(PrometheusError::ApiError(format!(
                         "Repository init error: {:?}",
@@ -181,18 +394,33 @@ pub fn get_repository() -> Arc<dyn PrometheusRepository> {
                 }
                 async fn get_metadata(
                     &self,
-                    _metric: &str,
+                    _metric: Option<&str>,
+                    _limit: Option<u64>,
+                    _limit_per_metric: Option<u64>,
                 ) -> Result<Vec<MetricMetadata>, PrometheusError> {
                     Err(PrometheusError::ApiError(format!(
                         "Repository init error: {:?}",
                         self.err
                     )))
                 }
+                async fn get_targets_metadata(
+                    &self,
+                    _match_target: Option<&str>,
+                    _metric: Option<&str>,
+                    _limit: Option<u64>,
+                ) -> Result<Vec<TargetMetadata>, PrometheusError> {
+                    Err(PrometheusError::ApiError(format!(
+                        "Repository init error: {:?}",
+                        self.err
+                    )))
+                }
                 async fn get_series(
                     &self,
                     _match_strings: Vec<&str>,
-                ) -> Result<Vec<std::collections::HashMap<String, String>>, PrometheusError>
-                {
+                    _start: Option<&str>,
+                    _end: Option<&str>,
+                    _limit: Option<u64>,
+                ) -> Result<SeriesResult, PrometheusError> {
                     Err(PrometheusError::ApiError(format!(
                         "Repository init error: {:?}",
                         self.err
@@ -201,12 +429,130 @@ pub fn get_repository() -> Arc<dyn PrometheusRepository> {
                 async fn get_label_values(
                     &self,
                     _label_name: &str,
+                    _match_strings: Vec<&str>,
+                    _start: Option<&str>,
+                    _end: Option<&str>,
+                    _limit: Option<u64>,
+                ) -> Result<Vec<String>, PrometheusError> {
+                    Err(PrometheusError::ApiError(format!(
+                        "Repository init error: {:?}",
+                        self.err
+                    )))
+                }
+                async fn list_labels(
+                    &self,
+                    _match_strings: Vec<&str>,
+                    _start: Option<&str>,
+                    _end: Option<&str>,
+                    _limit: Option<u64>,
                 ) -> Result<Vec<String>, PrometheusError> {
                     Err(PrometheusError::ApiError(format!(
                         "Repository init error: {:?}",
                         self.err
                     )))
                 }
+                async fn get_alerts(&self) -> Result<Vec<Alert>, PrometheusError> {
+                    Err(PrometheusError::ApiError(format!(
+                        "Repository init error: {:?}",
+                        self.err
+                    )))
+                }
+                async fn get_rules(
+                    &self,
+                    _rule_type: Option<&str>,
+                    _rule_names: Vec<&str>,
+                    _rule_groups: Vec<&str>,
+                    _files: Vec<&str>,
+                ) -> Result<Vec<RuleGroup>, PrometheusError> {
+                    Err(PrometheusError::ApiError(format!(
+                        "Repository init error: {:?}",
+                        self.err
+                    )))
+                }
+                async fn get_status(
+                    &self,
+                    _section: StatusSection,
+                ) -> Result<Value, PrometheusError> {
+                    Err(PrometheusError::ApiError(format!(
+                        "Repository init error: {:?}",
+                        self.err
+                    )))
+                }
+                async fn get_tsdb_status(
+                    &self,
+                    _limit: Option<u64>,
+                ) -> Result<TsdbStatus, PrometheusError> {
+                    Err(PrometheusError::ApiError(format!(
+                        "Repository init error: {:?}",
+                        self.err
+                    )))
+                }
+                async fn query_exemplars(
+                    &self,
+                    _query: &str,
+                    _start: Option<&str>,
+                    _end: Option<&str>,
+                ) -> Result<Vec<ExemplarSeries>, PrometheusError> {
+                    Err(PrometheusError::ApiError(format!(
+                        "Repository init error: {:?}",
+                        self.err
+                    )))
+                }
+                async fn format_query(&self, _query: &str) -> Result<String, PrometheusError> {
+                    Err(PrometheusError::ApiError(format!(
+                        "Repository init error: {:?}",
+                        self.err
+                    )))
+                }
+                async fn parse_query(&self, _query: &str) -> Result<Value, PrometheusError> {
+                    Err(PrometheusError::ApiError(format!(
+                        "Repository init error: {:?}",
+                        self.err
+                    )))
+                }
+                async fn snapshot(&self, _skip_head: bool) -> Result<String, PrometheusError> {
+                    Err(PrometheusError::ApiError(format!(
+                        "Repository init error: {:?}",
+                        self.err
+                    )))
+                }
+                async fn delete_series(
+                    &self,
+                    _match_strings: Vec<&str>,
+                    _start: Option<&str>,
+                    _end: Option<&str>,
+                ) -> Result<(), PrometheusError> {
+                    Err(PrometheusError::ApiError(format!(
+                        "Repository init error: {:?}",
+                        self.err
+                    )))
+                }
+                async fn clean_tombstones(&self) -> Result<(), PrometheusError> {
+                    Err(PrometheusError::ApiError(format!(
+                        "Repository init error: {:?}",
+                        self.err
+                    )))
+                }
+                async fn read_raw(
+                    &self,
+                    _selector: &str,
+                    _start_ms: i64,
+                    _end_ms: i64,
+                ) -> Result<Vec<RawSeries>, PrometheusError> {
+                    Err(PrometheusError::ApiError(format!(
+                        "Repository init error: {:?}",
+                        self.err
+                    )))
+                }
+                async fn federate(
+                    &self,
+                    _match_strings: Vec<&str>,
+                ) -> Result<Vec<MetricFamily>, PrometheusError> {
+                    Err(PrometheusError::ApiError(format!(
+                        "Repository init error: {:?}",
+                        self.err
+                    )))
+                }
             }
             let arc: Arc<dyn PrometheusRepository> = Arc::new(ErrRepo { err });
             *REPO.write().unwrap() = Some(Arc::clone(&arc));
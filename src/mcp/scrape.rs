@@ -0,0 +1,343 @@
+//! Scrape an arbitrary exposition endpoint (an exporter's or application's `/metrics`) and
+//! summarize what it exposes, for checking a target directly when a metric is missing
+//! from Prometheus.
+//!
+//! The URL comes from the model, so the tool only reaches hosts on an allowlist, speaks
+//! plain HTTP(S), does not follow redirects and stops reading after `MAX_BODY_BYTES`.
+
+use std::collections::{BTreeMap, HashSet};
+use std::sync::RwLock;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use reqwest::{redirect, Client};
+use serde::Serialize;
+use url::Url;
+
+use crate::mcp::exposition::{parse, Format, MetricFamily, Sample};
+use crate::mcp::prometheus_client::PrometheusError;
+
+/// Same preference order Prometheus sends when scraping
+const ACCEPT_HEADER: &str =
+    "application/openmetrics-text;version=1.0.0,text/plain;version=0.0.4;q=0.5,*/*;q=0.1";
+
+/// Largest response body read from a scrape target or remote-read endpoint
+pub const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+static ALLOWED_HOSTS: Lazy<RwLock<Vec<String>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Set the hosts the scrape tool may fetch from; an empty list disables the tool
+pub fn set_scrape_allowed_hosts(hosts: Vec<String>) {
+    *ALLOWED_HOSTS.write().unwrap() = hosts;
+}
+
+pub fn scrape_allowed_hosts() -> Vec<String> {
+    ALLOWED_HOSTS.read().unwrap().clone()
+}
+
+static TIMEOUT: Lazy<RwLock<Duration>> = Lazy::new(|| RwLock::new(Duration::from_secs(10)));
+
+/// Set the request timeout of the scrape tool, normally the Prometheus timeout
+pub fn set_scrape_timeout(timeout: Duration) {
+    *TIMEOUT.write().unwrap() = timeout;
+}
+
+pub fn scrape_timeout() -> Duration {
+    *TIMEOUT.read().unwrap()
+}
+
+/// Check `url` against the allowlist. Entries are a host (`node-exporter`), a host and
+/// port (`node-exporter:9100`), a domain suffix (`.monitoring.svc`) or `*` for any host.
+pub fn check_allowed_host(url: &str, allowed: &[String]) -> Result<(), PrometheusError> {
+    let parsed = parse_url(url)?;
+    let host = match parsed.host() {
+        Some(url::Host::Ipv6(ip)) => ip.to_string(),
+        Some(host) => host.to_string().to_ascii_lowercase(),
+        None => return Err(invalid_url(url, "no host")),
+    };
+    let port = parsed.port_or_known_default().unwrap_or_default();
+    let matches = |entry: &str| {
+        let entry = entry.trim().to_ascii_lowercase();
+        if entry == "*" {
+            return true;
+        }
+        if let Some(suffix) = entry.strip_prefix('.') {
+            return host == suffix || host.ends_with(&format!(".{}", suffix));
+        }
+        let (entry_host, entry_port) = split_host_port(&entry);
+        entry_host == host && entry_port.is_none_or(|p| p.parse::<u16>().ok() == Some(port))
+    };
+    if allowed.iter().any(|entry| matches(entry)) {
+        Ok(())
+    } else {
+        Err(PrometheusError::ApiError(format!(
+            "Host {} is not on the scrape allowlist (PROMETHEUS_SCRAPE_ALLOWED_HOSTS)",
+            host
+        )))
+    }
+}
+
+/// Split `host:port` or `[ipv6]:port`; a bare IPv6 address has no port
+fn split_host_port(entry: &str) -> (&str, Option<&str>) {
+    if let Some(rest) = entry.strip_prefix('[') {
+        return match rest.split_once(']') {
+            Some((host, tail)) => (host, tail.strip_prefix(':')),
+            None => (rest, None),
+        };
+    }
+    match entry.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') => (host, Some(port)),
+        _ => (entry, None),
+    }
+}
+
+fn parse_url(url: &str) -> Result<Url, PrometheusError> {
+    let parsed = Url::parse(url).map_err(|e| invalid_url(url, &e.to_string()))?;
+    match parsed.scheme() {
+        "http" | "https" => Ok(parsed),
+        scheme => Err(invalid_url(
+            url,
+            &format!("scheme {} is not supported, use http or https", scheme),
+        )),
+    }
+}
+
+fn invalid_url(url: &str, reason: &str) -> PrometheusError {
+    PrometheusError::ApiError(format!("Invalid scrape URL {}: {}", url, reason))
+}
+
+/// Summary of one scrape
+#[derive(Debug, Clone, Serialize)]
+pub struct ScrapeReport {
+    pub url: String,
+    pub format: Format,
+    /// Families and samples in the whole exposition, before filtering
+    pub family_count: usize,
+    pub sample_count: usize,
+    pub families: Vec<FamilySummary>,
+}
+
+/// One metric family with its sample count and label cardinality
+#[derive(Debug, Clone, Serialize)]
+pub struct FamilySummary {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub help: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    pub sample_count: usize,
+    /// Number of distinct values per label name
+    pub label_cardinality: BTreeMap<String, usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub samples: Option<Vec<Sample>>,
+}
+
+/// Fetch `url` and parse the body as OpenMetrics or text format, depending on its Content-Type.
+/// Only http and https URLs are fetched; redirects are returned as errors.
+pub async fn scrape(
+    url: &str,
+    timeout: Duration,
+) -> Result<(Format, Vec<MetricFamily>), PrometheusError> {
+    parse_url(url)?;
+    let client = Client::builder()
+        .timeout(timeout)
+        .redirect(redirect::Policy::none())
+        .build()
+        .map_err(|e| PrometheusError::BuildClientError(e.to_string()))?;
+    let mut response = client
+        .get(url)
+        .header(reqwest::header::ACCEPT, ACCEPT_HEADER)
+        .send()
+        .await?;
+
+    let status = response.status();
+    let format = match response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+    {
+        Some(ct) if ct.starts_with("application/openmetrics-text") => Format::OpenMetrics,
+        _ => Format::Text,
+    };
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > MAX_BODY_BYTES {
+            return Err(PrometheusError::ApiError(format!(
+                "Scrape of {} returned more than {} bytes",
+                url, MAX_BODY_BYTES
+            )));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    let body = String::from_utf8_lossy(&body);
+    if !status.is_success() {
+        return Err(PrometheusError::ApiError(format!(
+            "Scrape of {} returned {}: {}",
+            url,
+            status,
+            body.trim()
+        )));
+    }
+
+    let families = parse(&body, format).map_err(|e| {
+        PrometheusError::ParseError(format!("Failed to parse scrape response: {}", e))
+    })?;
+    Ok((format, families))
+}
+
+/// Summarize parsed families. With `metric`, only families named `metric` or containing a
+/// sample named `metric` (e.g. `foo_bucket`) are kept; `include_samples` adds their samples.
+pub fn summarize(
+    url: &str,
+    format: Format,
+    families: Vec<MetricFamily>,
+    metric: Option<&str>,
+    include_samples: bool,
+) -> ScrapeReport {
+    let family_count = families.len();
+    let sample_count = families.iter().map(|f| f.samples.len()).sum();
+
+    let families = families
+        .into_iter()
+        .filter(|f| match metric {
+            Some(m) => f.name == m || f.samples.iter().any(|s| s.name == m),
+            None => true,
+        })
+        .map(|f| {
+            let mut values: BTreeMap<String, HashSet<&str>> = BTreeMap::new();
+            for sample in &f.samples {
+                for (name, value) in &sample.labels {
+                    values.entry(name.clone()).or_default().insert(value);
+                }
+            }
+            let label_cardinality = values.into_iter().map(|(k, v)| (k, v.len())).collect();
+            FamilySummary {
+                sample_count: f.samples.len(),
+                label_cardinality,
+                samples: include_samples.then(|| f.samples.clone()),
+                name: f.name,
+                type_name: f.type_name,
+                help: f.help,
+                unit: f.unit,
+            }
+        })
+        .collect();
+
+    ScrapeReport {
+        url: url.to_string(),
+        format,
+        family_count,
+        sample_count,
+        families,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::test_server::{serve_once, Response};
+    use crate::mcp::{exporter, metrics};
+
+    #[test]
+    fn test_check_allowed_host() {
+        let allowed: Vec<String> = ["node-exporter:9100", ".monitoring.svc", "[::1]:9100"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert!(check_allowed_host("http://node-exporter:9100/metrics", &allowed).is_ok());
+        assert!(check_allowed_host("http://node-exporter:9200/metrics", &allowed).is_err());
+        assert!(check_allowed_host("https://app.monitoring.svc/metrics", &allowed).is_ok());
+        assert!(check_allowed_host("http://monitoring.svc.evil.com/", &allowed).is_err());
+        assert!(check_allowed_host("http://[::1]:9100/metrics", &allowed).is_ok());
+        assert!(check_allowed_host("http://169.254.169.254/latest/meta-data", &allowed).is_err());
+        assert!(check_allowed_host("http://anything/metrics", &["*".to_string()]).is_ok());
+        assert!(check_allowed_host("file:///etc/passwd", &["*".to_string()]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_scrape_rejects_redirects_and_large_bodies() {
+        let err = scrape("ftp://127.0.0.1/metrics", Duration::from_secs(2))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("scheme ftp"));
+
+        let (url, server) = serve_once(
+            Response::new("302 Found", "").header("Location", "http://169.254.169.254/"),
+        )
+        .await;
+        let err = scrape(&format!("{}/metrics", url), Duration::from_secs(2))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("302"), "{}", err);
+        server.await.unwrap();
+
+        let (url, server) = serve_once(
+            Response::new("200 OK", vec![b'#'; MAX_BODY_BYTES + 1])
+                .header("Content-Type", "text/plain"),
+        )
+        .await;
+        let err = scrape(&format!("{}/metrics", url), Duration::from_secs(5))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("more than"), "{}", err);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_scrape_metrics_exporter() {
+        metrics::init_metrics();
+        metrics::record_tool_call("prometheus_query");
+        metrics::record_tool_call("prometheus_scrape_target");
+        metrics::record_rpc_request("tools/call", 0.02);
+
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let (handle, shutdown) = exporter::create_metrics_server(port);
+        let url = format!("http://127.0.0.1:{}/metrics", port);
+
+        let mut scraped = None;
+        for _ in 0..50 {
+            match scrape(&url, Duration::from_secs(2)).await {
+                Ok(res) => {
+                    scraped = Some(res);
+                    break;
+                }
+                Err(_) => tokio::time::sleep(Duration::from_millis(20)).await,
+            }
+        }
+        let (format, families) = scraped.expect("exporter did not come up");
+        assert_eq!(format, Format::Text);
+
+        let report = summarize(&url, format, families.clone(), None, false);
+        assert_eq!(report.family_count, families.len());
+        let tools = report
+            .families
+            .iter()
+            .find(|f| f.name == "mcp_tool_calls_total")
+            .unwrap();
+        assert_eq!(tools.type_name, "counter");
+        assert_eq!(tools.help.as_deref(), Some("Total number of tool calls"));
+        assert!(tools.label_cardinality["tool"] >= 2);
+        assert!(tools.samples.is_none());
+
+        // Filtering by a histogram sample name keeps its family
+        let report = summarize(
+            &url,
+            format,
+            families,
+            Some("mcp_rpc_request_duration_seconds_bucket"),
+            true,
+        );
+        assert_eq!(report.families.len(), 1);
+        assert_eq!(report.families[0].type_name, "histogram");
+        assert!(report.families[0].samples.is_some());
+
+        let _ = shutdown.send(());
+        handle.await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,169 @@
+//! Tenant selection for multi-tenant backends (Mimir, Cortex, Thanos).
+//!
+//! Every Prometheus request carries the configured tenant header. A tool call may pick
+//! another tenant from the allowlist with its `tenant` argument; the choice is scoped to
+//! that call with a task-local, so concurrent calls never see each other's tenant.
+
+use std::future::Future;
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use reqwest::RequestBuilder;
+use serde_json::Value;
+
+use crate::mcp::prometheus_config::PrometheusConfig;
+
+/// Header Mimir and Cortex read the tenant from
+pub const DEFAULT_TENANT_HEADER: &str = "X-Scope-OrgID";
+
+/// Separator for querying several tenants at once (tenant federation)
+pub const TENANT_SEPARATOR: char = '|';
+
+tokio::task_local! {
+    static CALL_TENANT: String;
+}
+
+static ALLOWED_TENANTS: Lazy<RwLock<Vec<String>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Set the tenants a tool call may select; an empty list disables the `tenant` argument
+pub fn set_allowed_tenants(tenants: Vec<String>) {
+    *ALLOWED_TENANTS.write().unwrap() = tenants;
+}
+
+pub fn allowed_tenants() -> Vec<String> {
+    ALLOWED_TENANTS.read().unwrap().clone()
+}
+
+/// Run `fut` with `tenant` (if any) as the tenant of every Prometheus request it makes
+pub async fn with_tenant<F: Future>(tenant: Option<String>, fut: F) -> F::Output {
+    match tenant {
+        Some(tenant) => CALL_TENANT.scope(tenant, fut).await,
+        None => fut.await,
+    }
+}
+
+/// The tenant selected for the current tool call, if any
+pub fn call_tenant() -> Option<String> {
+    CALL_TENANT.try_with(|t| t.clone()).ok()
+}
+
+/// Set the tenant header on `rb`: the tenant selected for the current call, else the
+/// configured one
+pub fn apply_tenant(rb: RequestBuilder, config: &PrometheusConfig) -> RequestBuilder {
+    match call_tenant().or_else(|| config.tenant_id.clone()) {
+        Some(tenant) => rb.header(config.tenant_header.as_str(), tenant),
+        None => rb,
+    }
+}
+
+/// Check a requested tenant, or several joined with `|`, against the allowlist and
+/// return it normalized (parts trimmed, duplicates dropped)
+pub fn validate_tenant(requested: &str, allowed: &[String]) -> Result<String, String> {
+    if allowed.is_empty() {
+        return Err(
+            "Selecting a tenant per call is disabled; set PROMETHEUS_ALLOWED_TENANTS or --allowed-tenants"
+                .to_string(),
+        );
+    }
+    let mut tenants: Vec<&str> = Vec::new();
+    for part in requested.split(TENANT_SEPARATOR).map(str::trim) {
+        if part.is_empty() {
+            return Err(format!("Invalid tenant {:?}: empty tenant ID", requested));
+        }
+        if !allowed.iter().any(|a| a == part) {
+            return Err(format!(
+                "Tenant {:?} is not allowed; allowed tenants: {}",
+                part,
+                allowed.join(", ")
+            ));
+        }
+        if !tenants.contains(&part) {
+            tenants.push(part);
+        }
+    }
+    Ok(tenants.join(&TENANT_SEPARATOR.to_string()))
+}
+
+/// Whether `tool` offers the `tenant` argument: the Prometheus tools, except the
+/// scrape tool, which does not talk to Prometheus
+pub fn tool_accepts_tenant(tool: &str) -> bool {
+    tool.starts_with("prometheus_") && tool != "prometheus_scrape_target"
+}
+
+/// Remove the `tenant` argument from the arguments of a call to `tool` and validate it.
+/// Tools that do not offer the argument reject it instead of ignoring it.
+pub fn take_tenant_argument(
+    tool: &str,
+    arguments: &mut Option<Value>,
+) -> Result<Option<String>, String> {
+    let Some(args) = arguments.as_mut().and_then(Value::as_object_mut) else {
+        return Ok(None);
+    };
+    if !args.contains_key("tenant") {
+        return Ok(None);
+    }
+    if !tool_accepts_tenant(tool) {
+        return Err(format!("Tool {} does not take a tenant argument", tool));
+    }
+    match args.remove("tenant").unwrap_or_default() {
+        Value::Null => Ok(None),
+        Value::String(requested) => validate_tenant(&requested, &allowed_tenants()).map(Some),
+        other => Err(format!("Invalid tenant {}: expected a string", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_tenant() {
+        let allowed = vec!["team-a".to_string(), "team-b".to_string()];
+        assert_eq!(validate_tenant("team-a", &allowed).unwrap(), "team-a");
+        assert_eq!(
+            validate_tenant(" team-a | team-b |team-a", &allowed).unwrap(),
+            "team-a|team-b"
+        );
+        assert!(validate_tenant("team-c", &allowed)
+            .unwrap_err()
+            .contains("team-c"));
+        assert!(validate_tenant("team-a||team-b", &allowed).is_err());
+        assert!(validate_tenant("team-a", &[]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_with_tenant_scopes_the_call() {
+        assert_eq!(call_tenant(), None);
+        let inner = with_tenant(Some("team-a".into()), async { call_tenant() }).await;
+        assert_eq!(inner.as_deref(), Some("team-a"));
+        assert_eq!(with_tenant(None, async { call_tenant() }).await, None);
+        assert_eq!(call_tenant(), None);
+    }
+
+    #[test]
+    fn test_take_tenant_argument() {
+        let mut args = Some(json!({"query": "up"}));
+        assert_eq!(
+            take_tenant_argument("prometheus_query", &mut args),
+            Ok(None)
+        );
+
+        let mut args = Some(json!({"query": "up", "tenant": null}));
+        assert_eq!(
+            take_tenant_argument("prometheus_query", &mut args),
+            Ok(None)
+        );
+        assert_eq!(args, Some(json!({"query": "up"})));
+
+        let mut args = Some(json!({"query": "up", "tenant": 3}));
+        assert!(take_tenant_argument("prometheus_query", &mut args).is_err());
+
+        // Tools without the argument reject it
+        for tool in ["alertmanager_list_alerts", "prometheus_scrape_target"] {
+            let mut args = Some(json!({"tenant": "team-a"}));
+            let err = take_tenant_argument(tool, &mut args).unwrap_err();
+            assert!(err.contains("does not take a tenant"), "{}", err);
+        }
+    }
+}
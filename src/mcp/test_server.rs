@@ -0,0 +1,123 @@
+//! Stub HTTP server shared by the client tests.
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// A request received by the stub server
+pub struct Request {
+    /// Request line and headers, without the blank line
+    pub head: String,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    pub fn method(&self) -> &str {
+        self.head.split_whitespace().next().unwrap_or("")
+    }
+
+    /// Value of the header `name`, matched case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.head.lines().skip(1).find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.eq_ignore_ascii_case(name).then(|| value.trim())
+        })
+    }
+
+    pub fn body_str(&self) -> String {
+        String::from_utf8_lossy(&self.body).to_string()
+    }
+}
+
+/// A canned response; `Content-Length` and `Connection: close` are added when sent
+pub struct Response {
+    status: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    pub fn new(status: &str, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status: status.to_string(),
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    /// `200 OK` with a JSON body
+    pub fn json(body: &str) -> Self {
+        Self::new("200 OK", body).header("Content-Type", "application/json")
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+/// Listen on a free local port and answer `connections` requests with `respond`.
+/// Returns the base URL and a handle yielding the received requests.
+pub async fn serve<F>(connections: usize, mut respond: F) -> (String, JoinHandle<Vec<Request>>)
+where
+    F: FnMut(&Request) -> Response + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let handle = tokio::spawn(async move {
+        let mut requests = Vec::new();
+        for _ in 0..connections {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let request = read_request(&mut socket).await;
+            let response = respond(&request);
+            let mut head = format!("HTTP/1.1 {}\r\n", response.status);
+            for (name, value) in &response.headers {
+                head.push_str(&format!("{}: {}\r\n", name, value));
+            }
+            head.push_str(&format!(
+                "Content-Length: {}\r\nConnection: close\r\n\r\n",
+                response.body.len()
+            ));
+            // The client may hang up early, e.g. on an oversized body
+            let _ = socket.write_all(head.as_bytes()).await;
+            let _ = socket.write_all(&response.body).await;
+            requests.push(request);
+        }
+        requests
+    });
+    (url, handle)
+}
+
+/// Answer a single request with `response`
+pub async fn serve_once(response: Response) -> (String, JoinHandle<Request>) {
+    let mut response = Some(response);
+    let (url, handle) = serve(1, move |_| response.take().unwrap()).await;
+    let handle = tokio::spawn(async move { handle.await.unwrap().pop().unwrap() });
+    (url, handle)
+}
+
+async fn read_request(socket: &mut TcpStream) -> Request {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let head_len = loop {
+        let n = socket.read(&mut chunk).await.unwrap();
+        assert!(n > 0, "connection closed before the request head");
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+    };
+    let mut request = Request {
+        head: String::from_utf8_lossy(&buf[..head_len]).to_string(),
+        body: buf.split_off(head_len + 4),
+    };
+    let body_len = request
+        .header("content-length")
+        .map_or(0, |v| v.parse::<usize>().unwrap());
+    while request.body.len() < body_len {
+        let n = socket.read(&mut chunk).await.unwrap();
+        assert!(n > 0, "connection closed before the request body");
+        request.body.extend_from_slice(&chunk[..n]);
+    }
+    request
+}
@@ -0,0 +1,132 @@
+//! Timestamp parsing and formatting shared by the tools: Unix seconds or RFC3339 in,
+//! RFC3339 in UTC out, at millisecond precision.
+
+/// Parse a timestamp given as Unix seconds (`1700000000.5`) or RFC3339
+/// (`2023-11-14T22:13:20Z`, `2023-11-14T23:13:20.5+01:00`) into milliseconds
+pub fn parse_timestamp_ms(input: &str) -> Result<i64, String> {
+    let s = input.trim();
+    if let Ok(secs) = s.parse::<f64>() {
+        if secs.is_finite() {
+            return Ok((secs * 1000.0).round() as i64);
+        }
+    }
+    parse_rfc3339_ms(s).ok_or_else(|| format!("invalid timestamp: {}", input))
+}
+
+fn parse_rfc3339_ms(s: &str) -> Option<i64> {
+    let b = s.as_bytes();
+    if b.len() < 20 || b[4] != b'-' || b[7] != b'-' || !matches!(b[10], b'T' | b't' | b' ') {
+        return None;
+    }
+    let num = |range: std::ops::Range<usize>| s.get(range)?.parse::<i64>().ok();
+    let (year, month, day) = (num(0..4)?, num(5..7)?, num(8..10)?);
+    let (hour, min, sec) = (num(11..13)?, num(14..16)?, num(17..19)?);
+    if b[13] != b':' || b[16] != b':' || !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let mut rest = &s[19..];
+    let mut millis = 0i64;
+    if let Some(frac) = rest.strip_prefix('.') {
+        let digits = frac.bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 {
+            return None;
+        }
+        let padded = format!("{:0<3}", &frac[..digits.min(3)]);
+        millis = padded.parse().ok()?;
+        rest = &frac[digits..];
+    }
+    let offset_secs = match rest {
+        "Z" | "z" => 0,
+        _ => {
+            let sign = match rest.as_bytes().first()? {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            if rest.len() != 6 || rest.as_bytes()[3] != b':' {
+                return None;
+            }
+            sign * (rest[1..3].parse::<i64>().ok()? * 3600 + rest[4..6].parse::<i64>().ok()? * 60)
+        }
+    };
+
+    // Days since the Unix epoch for a proleptic Gregorian date (Howard Hinnant's algorithm)
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146097 + doe - 719468;
+
+    let secs = days * 86400 + hour * 3600 + min * 60 + sec - offset_secs;
+    Some(secs * 1000 + millis)
+}
+
+/// Format milliseconds since the Unix epoch as RFC3339 in UTC (`2023-11-14T22:13:20.500Z`)
+pub fn format_rfc3339_ms(ms: i64) -> String {
+    let secs = ms.div_euclid(1000);
+    let millis = ms.rem_euclid(1000);
+    let days = secs.div_euclid(86400);
+    let tod = secs.rem_euclid(86400);
+
+    // Inverse of the days computation in parse_rfc3339_ms
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        tod / 3600,
+        tod % 3600 / 60,
+        tod % 60,
+        millis
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timestamp_ms() {
+        assert_eq!(parse_timestamp_ms("1700000000").unwrap(), 1_700_000_000_000);
+        assert_eq!(
+            parse_timestamp_ms("1700000000.123").unwrap(),
+            1_700_000_000_123
+        );
+        assert_eq!(
+            parse_timestamp_ms("2023-11-14T22:13:20Z").unwrap(),
+            1_700_000_000_000
+        );
+        assert_eq!(
+            parse_timestamp_ms("2023-11-14T23:13:20.5+01:00").unwrap(),
+            1_700_000_000_500
+        );
+        assert_eq!(parse_timestamp_ms("1969-12-31T23:59:59Z").unwrap(), -1000);
+        assert!(parse_timestamp_ms("2023-13-01T00:00:00Z").is_err());
+        assert!(parse_timestamp_ms("now").is_err());
+    }
+
+    #[test]
+    fn test_format_rfc3339_ms() {
+        assert_eq!(
+            format_rfc3339_ms(1_700_000_000_500),
+            "2023-11-14T22:13:20.500Z"
+        );
+        assert_eq!(format_rfc3339_ms(-1000), "1969-12-31T23:59:59.000Z");
+        for ms in [0, 951_782_400_000, 1_709_164_799_999, 4_107_542_400_000] {
+            assert_eq!(parse_timestamp_ms(&format_rfc3339_ms(ms)).unwrap(), ms);
+        }
+    }
+}
@@ -1,13 +1,75 @@
+use crate::mcp::alertmanager_client::{
+    get_alertmanager, parse_duration_ms, AlertFilter, AlertmanagerRepository, GettableAlert,
+    Silence, SilenceMatcher,
+};
+use crate::mcp::matchers::{matches_all, parse_matchers, LabelMatcher};
 use crate::mcp::metrics;
+use crate::mcp::prometheus_client::{
+    Alert, PrometheusError, PrometheusQueryResult, QueryOptions, StatusSection,
+};
+use crate::mcp::prometheus_config::BackendFlavor;
+use crate::mcp::redact::redact_config;
 use crate::mcp::repository::get_repository;
+use crate::mcp::scrape::{self, scrape_allowed_hosts, scrape_timeout};
+use crate::mcp::tenant::{allowed_tenants, tool_accepts_tenant};
+use crate::mcp::time::{format_rfc3339_ms, parse_timestamp_ms};
 use crate::mcp::types::*;
 use maplit::hashmap;
 use rpc_router::{Handler, HandlerResult, RouterBuilder, RpcParams};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Whether the TSDB admin tools are registered and allowed to run
+static ADMIN_TOOLS_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Maximum number of series listed in a delete_series dry run
+const DRY_RUN_SERIES_LIMIT: u64 = 100;
+
+/// Enable or disable the TSDB admin tools; call before building the router
+pub fn set_admin_tools_enabled(enabled: bool) {
+    ADMIN_TOOLS_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn admin_tools_enabled() -> bool {
+    ADMIN_TOOLS_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Backend behind the Prometheus URL; decides which extra query arguments are offered
+static BACKEND_FLAVOR: RwLock<BackendFlavor> = RwLock::new(BackendFlavor::Prometheus);
+
+/// Set the backend flavor; call before building the router
+pub fn set_backend_flavor(flavor: BackendFlavor) {
+    *BACKEND_FLAVOR.write().unwrap() = flavor;
+}
+
+pub fn backend_flavor() -> BackendFlavor {
+    *BACKEND_FLAVOR.read().unwrap()
+}
+
+/// Whether the tools that create, extend and expire Alertmanager silences are registered
+static SILENCE_TOOLS_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Maximum number of alerts listed in a silence preview
+const SILENCE_PREVIEW_ALERT_LIMIT: usize = 100;
+
+/// Enable or disable the silence write tools; call before building the router
+pub fn set_silence_tools_enabled(enabled: bool) {
+    SILENCE_TOOLS_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn silence_tools_enabled() -> bool {
+    SILENCE_TOOLS_ENABLED.load(Ordering::Relaxed)
+}
 
 /// register all tools to the router
 pub fn register_tools(router_builder: RouterBuilder) -> RouterBuilder {
-    router_builder
+    let router_builder = router_builder
         .append_dyn("tools/list", tools_list.into_dyn())
         .append_dyn("prometheus_query", prometheus_query.into_dyn())
         .append_dyn("prometheus_query_range", prometheus_query_range.into_dyn())
@@ -19,11 +81,96 @@ pub fn register_tools(router_builder: RouterBuilder) -> RouterBuilder {
             "prometheus_get_metadata",
             prometheus_get_metadata.into_dyn(),
         )
+        .append_dyn(
+            "prometheus_get_targets_metadata",
+            prometheus_get_targets_metadata.into_dyn(),
+        )
         .append_dyn("prometheus_get_series", prometheus_get_series.into_dyn())
         .append_dyn(
             "prometheus_get_label_values",
             prometheus_get_label_values.into_dyn(),
         )
+        .append_dyn("prometheus_list_labels", prometheus_list_labels.into_dyn())
+        .append_dyn("prometheus_get_alerts", prometheus_get_alerts.into_dyn())
+        .append_dyn("prometheus_get_rules", prometheus_get_rules.into_dyn())
+        .append_dyn("prometheus_get_status", prometheus_get_status.into_dyn())
+        .append_dyn("prometheus_tsdb_status", prometheus_tsdb_status.into_dyn())
+        .append_dyn(
+            "prometheus_query_exemplars",
+            prometheus_query_exemplars.into_dyn(),
+        )
+        .append_dyn(
+            "prometheus_format_query",
+            prometheus_format_query.into_dyn(),
+        )
+        .append_dyn("prometheus_parse_query", prometheus_parse_query.into_dyn())
+        .append_dyn("prometheus_read_raw", prometheus_read_raw.into_dyn())
+        .append_dyn("prometheus_federate", prometheus_federate.into_dyn());
+
+    let router_builder = if scrape_allowed_hosts().is_empty() {
+        router_builder
+    } else {
+        router_builder.append_dyn(
+            "prometheus_scrape_target",
+            prometheus_scrape_target.into_dyn(),
+        )
+    };
+
+    let router_builder = if admin_tools_enabled() {
+        router_builder
+            .append_dyn(
+                "prometheus_admin_snapshot",
+                prometheus_admin_snapshot.into_dyn(),
+            )
+            .append_dyn(
+                "prometheus_admin_delete_series",
+                prometheus_admin_delete_series.into_dyn(),
+            )
+            .append_dyn(
+                "prometheus_admin_clean_tombstones",
+                prometheus_admin_clean_tombstones.into_dyn(),
+            )
+    } else {
+        router_builder
+    };
+
+    if get_alertmanager().is_none() {
+        return router_builder;
+    }
+    let router_builder = router_builder
+        .append_dyn(
+            "alertmanager_list_alerts",
+            alertmanager_list_alerts.into_dyn(),
+        )
+        .append_dyn(
+            "alertmanager_list_alert_groups",
+            alertmanager_list_alert_groups.into_dyn(),
+        )
+        .append_dyn(
+            "alertmanager_get_status",
+            alertmanager_get_status.into_dyn(),
+        )
+        .append_dyn(
+            "alertmanager_list_silences",
+            alertmanager_list_silences.into_dyn(),
+        );
+
+    if !silence_tools_enabled() {
+        return router_builder;
+    }
+    router_builder
+        .append_dyn(
+            "alertmanager_create_silence",
+            alertmanager_create_silence.into_dyn(),
+        )
+        .append_dyn(
+            "alertmanager_extend_silence",
+            alertmanager_extend_silence.into_dyn(),
+        )
+        .append_dyn(
+            "alertmanager_expire_silence",
+            alertmanager_expire_silence.into_dyn(),
+        )
 }
 
 pub async fn tools_list(_request: Option<ListToolsRequest>) -> HandlerResult<ListToolsResult> {
@@ -44,6 +191,27 @@ pub async fn tools_list(_request: Option<ListToolsRequest>) -> HandlerResult<Lis
                 enum_values: None,
                 items: None,
                 min_items: None,
+            },
+            "stats".to_string() => ToolInputSchemaProperty {
+                type_name: Some("boolean".to_owned()),
+                description: Some("Return query statistics (samples processed, peak samples, timings)".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "timeout".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("Server-side evaluation timeout (e.g. 10s); capped by Prometheus' -query.timeout".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "limit".to_string() => ToolInputSchemaProperty {
+                type_name: Some("integer".to_owned()),
+                description: Some("Maximum number of returned series (Prometheus 3.x)".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
             }
         },
         required: vec!["query".to_string()],
@@ -80,6 +248,27 @@ pub async fn tools_list(_request: Option<ListToolsRequest>) -> HandlerResult<Lis
                 enum_values: None,
                 items: None,
                 min_items: None,
+            },
+            "stats".to_string() => ToolInputSchemaProperty {
+                type_name: Some("boolean".to_owned()),
+                description: Some("Return query statistics (samples processed, peak samples, timings)".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "timeout".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("Server-side evaluation timeout (e.g. 10s); capped by Prometheus' -query.timeout".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "limit".to_string() => ToolInputSchemaProperty {
+                type_name: Some("integer".to_owned()),
+                description: Some("Maximum number of returned series (Prometheus 3.x)".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
             }
         },
         required: vec![
@@ -103,13 +292,56 @@ pub async fn tools_list(_request: Option<ListToolsRequest>) -> HandlerResult<Lis
         properties: hashmap! {
             "metric".to_string() => ToolInputSchemaProperty {
                 type_name: Some("string".to_owned()),
-                description: Some("Metric name".to_owned()),
+                description: Some("Metric name; omit to list metadata for all metrics".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "limit".to_string() => ToolInputSchemaProperty {
+                type_name: Some("integer".to_owned()),
+                description: Some("Maximum number of metrics to return".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "limit_per_metric".to_string() => ToolInputSchemaProperty {
+                type_name: Some("integer".to_owned()),
+                description: Some("Maximum number of metadata entries to return per metric".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            }
+        },
+        required: vec![],
+        additional_properties: Some(false),
+    };
+
+    let targets_metadata_schema = ToolInputSchema {
+        type_name: "object".to_string(),
+        properties: hashmap! {
+            "match_target".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("Label selector for targets (e.g. {job=\"node\"}); omit to match all targets".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "metric".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("Metric name; omit to return metadata for all metrics".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "limit".to_string() => ToolInputSchemaProperty {
+                type_name: Some("integer".to_owned()),
+                description: Some("Maximum number of targets to match".to_owned()),
                 enum_values: None,
                 items: None,
                 min_items: None,
             }
         },
-        required: vec!["metric".to_string()],
+        required: vec![],
         additional_properties: Some(false),
     };
 
@@ -128,6 +360,27 @@ pub async fn tools_list(_request: Option<ListToolsRequest>) -> HandlerResult<Lis
                     min_items: None,
                 })),
                 min_items: Some(1),
+            },
+            "start".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("Start timestamp (RFC3339 or Unix timestamp)".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "end".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("End timestamp (RFC3339 or Unix timestamp)".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "limit".to_string() => ToolInputSchemaProperty {
+                type_name: Some("integer".to_owned()),
+                description: Some("Maximum number of series to return".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
             }
         },
         required: vec!["match_strings".to_string()],
@@ -143,75 +396,1628 @@ pub async fn tools_list(_request: Option<ListToolsRequest>) -> HandlerResult<Lis
                 enum_values: None,
                 items: None,
                 min_items: None,
+            },
+            "match_strings".to_string() => ToolInputSchemaProperty {
+                type_name: Some("array".to_owned()),
+                description: Some("Only consider series matching these selectors (e.g. ['up{job=\"api\"}'])".to_owned()),
+                enum_values: None,
+                items: Some(Box::new(ToolInputSchemaProperty {
+                    type_name: Some("string".to_owned()),
+                    enum_values: None,
+                    description: None,
+                    items: None,
+                    min_items: None,
+                })),
+                min_items: None,
+            },
+            "start".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("Start timestamp (RFC3339 or Unix timestamp)".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "end".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("End timestamp (RFC3339 or Unix timestamp)".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "limit".to_string() => ToolInputSchemaProperty {
+                type_name: Some("integer".to_owned()),
+                description: Some("Maximum number of entries to return".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
             }
         },
         required: vec!["label_name".to_string()],
         additional_properties: Some(false),
     };
 
-    let response = ListToolsResult {
-        tools: vec![
-            Tool {
-                name: "prometheus_query".to_string(),
-                description: Some("Execute a Prometheus instant query".to_string()),
-                parameters: Some(query_schema.clone()),
-                input_schema: query_schema,
-            },
-            Tool {
-                name: "prometheus_query_range".to_string(),
-                description: Some("Execute a Prometheus range query".to_string()),
-                parameters: Some(range_schema.clone()),
-                input_schema: range_schema,
+    let list_labels_schema = ToolInputSchema {
+        type_name: "object".to_string(),
+        properties: hashmap! {
+            "match_strings".to_string() => ToolInputSchemaProperty {
+                type_name: Some("array".to_owned()),
+                description: Some("Only consider series matching these selectors (e.g. ['up{job=\"api\"}'])".to_owned()),
+                enum_values: None,
+                items: Some(Box::new(ToolInputSchemaProperty {
+                    type_name: Some("string".to_owned()),
+                    enum_values: None,
+                    description: None,
+                    items: None,
+                    min_items: None,
+                })),
+                min_items: None,
             },
-            Tool {
-                name: "prometheus_list_metrics".to_string(),
-                description: Some(
-                    "List all metric names that can be queried from Prometheus".to_string(),
-                ),
-                parameters: Some(list_metrics_schema.clone()),
-                input_schema: list_metrics_schema,
+            "start".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("Start timestamp (RFC3339 or Unix timestamp)".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
             },
-            Tool {
-                name: "prometheus_get_metadata".to_string(),
-                description: Some("Get metadata about a specific metric".to_string()),
-                parameters: Some(metadata_schema.clone()),
-                input_schema: metadata_schema,
+            "end".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("End timestamp (RFC3339 or Unix timestamp)".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
             },
-            Tool {
-                name: "prometheus_get_series".to_string(),
-                description: Some(
-                    "Get time series data for a specific metric with optional label matchers"
-                        .to_string(),
-                ),
-                parameters: Some(series_schema.clone()),
-                input_schema: series_schema,
+            "limit".to_string() => ToolInputSchemaProperty {
+                type_name: Some("integer".to_owned()),
+                description: Some("Maximum number of entries to return".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            }
+        },
+        required: vec![],
+        additional_properties: Some(false),
+    };
+
+    let alerts_schema = ToolInputSchema {
+        type_name: "object".to_string(),
+        properties: hashmap! {
+            "state".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("Only return alerts in this state".to_owned()),
+                enum_values: Some(vec!["firing".to_owned(), "pending".to_owned()]),
+                items: None,
+                min_items: None,
             },
-            Tool {
-                name: "prometheus_get_label_values".to_string(),
-                description: Some("Get all label values for a specific label name".to_string()),
-                parameters: Some(label_values_schema.clone()),
-                input_schema: label_values_schema,
+            "alertname".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("Only return alerts with this alertname".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
             },
-        ],
-        next_cursor: None,
+            "matchers".to_string() => ToolInputSchemaProperty {
+                type_name: Some("array".to_owned()),
+                description: Some("Label matchers the alert labels must satisfy (e.g. ['severity=\"critical\"', 'team=~\"db.*\"'])".to_owned()),
+                enum_values: None,
+                items: Some(Box::new(ToolInputSchemaProperty {
+                    type_name: Some("string".to_owned()),
+                    enum_values: None,
+                    description: None,
+                    items: None,
+                    min_items: None,
+                })),
+                min_items: None,
+            }
+        },
+        required: vec![],
+        additional_properties: Some(false),
     };
-    Ok(response)
-}
-
-#[derive(Deserialize, Serialize, RpcParams)]
-pub struct PrometheusQueryRequest {
-    pub query: String,
-    pub time: Option<String>,
-}
 
-pub async fn prometheus_query(request: PrometheusQueryRequest) -> HandlerResult<CallToolResult> {
+    let rules_schema = ToolInputSchema {
+        type_name: "object".to_string(),
+        properties: hashmap! {
+            "type".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("Only return alerting (alert) or recording (record) rules".to_owned()),
+                enum_values: Some(vec!["alert".to_owned(), "record".to_owned()]),
+                items: None,
+                min_items: None,
+            },
+            "rule_name".to_string() => ToolInputSchemaProperty {
+                type_name: Some("array".to_owned()),
+                description: Some("Only return rules with these names".to_owned()),
+                enum_values: None,
+                items: Some(Box::new(ToolInputSchemaProperty {
+                    type_name: Some("string".to_owned()),
+                    enum_values: None,
+                    description: None,
+                    items: None,
+                    min_items: None,
+                })),
+                min_items: None,
+            },
+            "rule_group".to_string() => ToolInputSchemaProperty {
+                type_name: Some("array".to_owned()),
+                description: Some("Only return rules from these rule groups".to_owned()),
+                enum_values: None,
+                items: Some(Box::new(ToolInputSchemaProperty {
+                    type_name: Some("string".to_owned()),
+                    enum_values: None,
+                    description: None,
+                    items: None,
+                    min_items: None,
+                })),
+                min_items: None,
+            },
+            "file".to_string() => ToolInputSchemaProperty {
+                type_name: Some("array".to_owned()),
+                description: Some("Only return rules from these rule files".to_owned()),
+                enum_values: None,
+                items: Some(Box::new(ToolInputSchemaProperty {
+                    type_name: Some("string".to_owned()),
+                    enum_values: None,
+                    description: None,
+                    items: None,
+                    min_items: None,
+                })),
+                min_items: None,
+            }
+        },
+        required: vec![],
+        additional_properties: Some(false),
+    };
+
+    let status_schema = ToolInputSchema {
+        type_name: "object".to_string(),
+        properties: hashmap! {
+            "section".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("Status section: buildinfo (version), runtimeinfo (retention, storage), flags (command-line flags) or config (loaded configuration, secrets redacted)".to_owned()),
+                enum_values: Some(vec![
+                    "buildinfo".to_owned(),
+                    "runtimeinfo".to_owned(),
+                    "flags".to_owned(),
+                    "config".to_owned(),
+                ]),
+                items: None,
+                min_items: None,
+            }
+        },
+        required: vec!["section".to_string()],
+        additional_properties: Some(false),
+    };
+
+    let tsdb_status_schema = ToolInputSchema {
+        type_name: "object".to_string(),
+        properties: hashmap! {
+            "limit".to_string() => ToolInputSchemaProperty {
+                type_name: Some("integer".to_owned()),
+                description: Some("Number of top entries to return per list (Prometheus default: 10)".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            }
+        },
+        required: vec![],
+        additional_properties: Some(false),
+    };
+
+    let exemplars_schema = ToolInputSchema {
+        type_name: "object".to_string(),
+        properties: hashmap! {
+            "query".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("Prometheus query selecting the series to fetch exemplars for".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "start".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("Start timestamp (RFC3339 or Unix timestamp)".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "end".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("End timestamp (RFC3339 or Unix timestamp)".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            }
+        },
+        required: vec!["query".to_string()],
+        additional_properties: Some(false),
+    };
+
+    let promql_schema = ToolInputSchema {
+        type_name: "object".to_string(),
+        properties: hashmap! {
+            "query".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("PromQL expression".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            }
+        },
+        required: vec!["query".to_string()],
+        additional_properties: Some(false),
+    };
+
+    let read_raw_schema = ToolInputSchema {
+        type_name: "object".to_string(),
+        properties: hashmap! {
+            "selector".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("Series selector, e.g. `up{job=\"node\"}`".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "start".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("Start timestamp (RFC3339 or Unix timestamp)".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "end".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("End timestamp (RFC3339 or Unix timestamp)".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            }
+        },
+        required: vec![
+            "selector".to_string(),
+            "start".to_string(),
+            "end".to_string(),
+        ],
+        additional_properties: Some(false),
+    };
+
+    let federate_schema = ToolInputSchema {
+        type_name: "object".to_string(),
+        properties: hashmap! {
+            "match_strings".to_string() => ToolInputSchemaProperty {
+                type_name: Some("array".to_owned()),
+                description: Some("Series selectors to federate (e.g. ['{job=\"node\"}'])".to_owned()),
+                enum_values: None,
+                items: Some(Box::new(ToolInputSchemaProperty {
+                    type_name: Some("string".to_owned()),
+                    enum_values: None,
+                    description: None,
+                    items: None,
+                    min_items: None,
+                })),
+                min_items: Some(1),
+            }
+        },
+        required: vec!["match_strings".to_string()],
+        additional_properties: Some(false),
+    };
+
+    let scrape_schema = ToolInputSchema {
+        type_name: "object".to_string(),
+        properties: hashmap! {
+            "url".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("Exposition endpoint to fetch, e.g. http://node-exporter:9100/metrics".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "metric".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("Only report the family with this name, or containing a sample with this name".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "include_samples".to_string() => ToolInputSchemaProperty {
+                type_name: Some("boolean".to_owned()),
+                description: Some("Include the parsed samples of each reported family (default: false)".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            }
+        },
+        required: vec!["url".to_string()],
+        additional_properties: Some(false),
+    };
+
+    let mut response = ListToolsResult {
+        tools: vec![
+            Tool {
+                name: "prometheus_query".to_string(),
+                description: Some("Execute a Prometheus instant query".to_string()),
+                parameters: Some(query_schema.clone()),
+                input_schema: query_schema,
+                annotations: None,
+            },
+            Tool {
+                name: "prometheus_query_range".to_string(),
+                description: Some("Execute a Prometheus range query".to_string()),
+                parameters: Some(range_schema.clone()),
+                input_schema: range_schema,
+                annotations: None,
+            },
+            Tool {
+                name: "prometheus_list_metrics".to_string(),
+                description: Some(
+                    "List all metric names that can be queried from Prometheus".to_string(),
+                ),
+                parameters: Some(list_metrics_schema.clone()),
+                input_schema: list_metrics_schema,
+                annotations: None,
+            },
+            Tool {
+                name: "prometheus_get_metadata".to_string(),
+                description: Some(
+                    "Get metadata (type, help, unit) for a metric, or for all metrics".to_string(),
+                ),
+                parameters: Some(metadata_schema.clone()),
+                input_schema: metadata_schema,
+                annotations: None,
+            },
+            Tool {
+                name: "prometheus_get_targets_metadata".to_string(),
+                description: Some(
+                    "Get metric metadata per scrape target, showing which targets expose a metric"
+                        .to_string(),
+                ),
+                parameters: Some(targets_metadata_schema.clone()),
+                input_schema: targets_metadata_schema,
+                annotations: None,
+            },
+            Tool {
+                name: "prometheus_get_series".to_string(),
+                description: Some(
+                    "Get time series data for a specific metric with optional label matchers"
+                        .to_string(),
+                ),
+                parameters: Some(series_schema.clone()),
+                input_schema: series_schema,
+                annotations: None,
+            },
+            Tool {
+                name: "prometheus_get_label_values".to_string(),
+                description: Some("Get all label values for a specific label name".to_string()),
+                parameters: Some(label_values_schema.clone()),
+                input_schema: label_values_schema,
+                annotations: None,
+            },
+            Tool {
+                name: "prometheus_list_labels".to_string(),
+                description: Some(
+                    "List label names, optionally restricted to matching series and a time range"
+                        .to_string(),
+                ),
+                parameters: Some(list_labels_schema.clone()),
+                input_schema: list_labels_schema,
+                annotations: None,
+            },
+            Tool {
+                name: "prometheus_get_alerts".to_string(),
+                description: Some(
+                    "Get active alerts with their state, activeAt, value, labels and annotations"
+                        .to_string(),
+                ),
+                parameters: Some(alerts_schema.clone()),
+                input_schema: alerts_schema,
+                annotations: None,
+            },
+            Tool {
+                name: "prometheus_get_rules".to_string(),
+                description: Some(
+                    "Get alerting and recording rule groups with rule expressions, durations, health and current alerts"
+                        .to_string(),
+                ),
+                parameters: Some(rules_schema.clone()),
+                input_schema: rules_schema,
+                annotations: None,
+            },
+            Tool {
+                name: "prometheus_get_status".to_string(),
+                description: Some(
+                    "Get Prometheus server status: build info, runtime info, flags or configuration"
+                        .to_string(),
+                ),
+                parameters: Some(status_schema.clone()),
+                input_schema: status_schema,
+                annotations: None,
+            },
+            Tool {
+                name: "prometheus_tsdb_status".to_string(),
+                description: Some(
+                    "Get TSDB cardinality statistics: head stats, top metrics by series count, top label names by value count and memory, top label-value pairs"
+                        .to_string(),
+                ),
+                parameters: Some(tsdb_status_schema.clone()),
+                input_schema: tsdb_status_schema,
+                annotations: None,
+            },
+            Tool {
+                name: "prometheus_query_exemplars".to_string(),
+                description: Some(
+                    "Query exemplars (with trace IDs and labels) for the series selected by a query"
+                        .to_string(),
+                ),
+                parameters: Some(exemplars_schema.clone()),
+                input_schema: exemplars_schema,
+                annotations: None,
+            },
+            Tool {
+                name: "prometheus_format_query".to_string(),
+                description: Some(
+                    "Format a PromQL expression into its canonical, readable form using the server's formatter"
+                        .to_string(),
+                ),
+                parameters: Some(promql_schema.clone()),
+                input_schema: promql_schema.clone(),
+                annotations: None,
+            },
+            Tool {
+                name: "prometheus_parse_query".to_string(),
+                description: Some(
+                    "Parse a PromQL expression and return its syntax tree as JSON".to_string(),
+                ),
+                parameters: Some(promql_schema.clone()),
+                input_schema: promql_schema,
+                annotations: None,
+            },
+            Tool {
+                name: "prometheus_read_raw".to_string(),
+                description: Some(
+                    "Read the raw stored samples (millisecond timestamps, no PromQL evaluation) of the series matching a selector in a time range"
+                        .to_string(),
+                ),
+                parameters: Some(read_raw_schema.clone()),
+                input_schema: read_raw_schema,
+                annotations: None,
+            },
+            Tool {
+                name: "prometheus_federate".to_string(),
+                description: Some(
+                    "Read the latest samples of the matching series from /federate, grouped into metric families with type, labels and timestamps"
+                        .to_string(),
+                ),
+                parameters: Some(federate_schema.clone()),
+                input_schema: federate_schema,
+                annotations: None,
+            },
+        ],
+        next_cursor: None,
+    };
+    if !scrape_allowed_hosts().is_empty() {
+        response.tools.push(Tool {
+            name: "prometheus_scrape_target".to_string(),
+            description: Some(
+                "Fetch a target's own /metrics endpoint (Prometheus text or OpenMetrics) and report its metric families, types, help, sample counts and label cardinality"
+                    .to_string(),
+            ),
+            parameters: Some(scrape_schema.clone()),
+            input_schema: scrape_schema,
+            annotations: None,
+        });
+    }
+    if admin_tools_enabled() {
+        response.tools.extend(admin_tools());
+    }
+    if get_alertmanager().is_some() {
+        response.tools.extend(alertmanager_tools());
+        if silence_tools_enabled() {
+            response.tools.extend(silence_tools());
+        }
+    }
+    if backend_flavor() == BackendFlavor::Thanos {
+        add_thanos_query_arguments(&mut response.tools);
+    }
+    add_tenant_argument(&mut response.tools, &allowed_tenants());
+    Ok(response)
+}
+
+/// Offer the Thanos query parameters on the instant and range query tools
+fn add_thanos_query_arguments(tools: &mut [Tool]) {
+    let property = |type_name: &str, description: &str, enum_values: Option<Vec<String>>| {
+        ToolInputSchemaProperty {
+            type_name: Some(type_name.to_owned()),
+            description: Some(description.to_owned()),
+            enum_values,
+            items: None,
+            min_items: None,
+        }
+    };
+    let properties = [
+        (
+            "dedup",
+            property(
+                "boolean",
+                "Deduplicate series from HA replicas (Thanos default: true)",
+                None,
+            ),
+        ),
+        (
+            "partial_response",
+            property(
+                "boolean",
+                "Return partial results with warnings when some stores fail instead of an error",
+                None,
+            ),
+        ),
+        (
+            "max_source_resolution",
+            property(
+                "string",
+                "Coarsest downsampled data to use: 0s (raw), 5m, 1h or auto (from the step); use 5m or 1h for long ranges",
+                None,
+            ),
+        ),
+        (
+            "engine",
+            property(
+                "string",
+                "PromQL engine to evaluate the query",
+                Some(vec!["prometheus".to_owned(), "thanos".to_owned()]),
+            ),
+        ),
+    ];
+    for tool in tools
+        .iter_mut()
+        .filter(|t| t.name == "prometheus_query" || t.name == "prometheus_query_range")
+    {
+        for (name, prop) in &properties {
+            tool.input_schema
+                .properties
+                .insert(name.to_string(), prop.clone());
+            if let Some(parameters) = tool.parameters.as_mut() {
+                parameters.properties.insert(name.to_string(), prop.clone());
+            }
+        }
+    }
+}
+
+/// Offer the `tenant` argument on every tool that talks to Prometheus when tenants are
+/// selectable per call
+fn add_tenant_argument(tools: &mut [Tool], allowed: &[String]) {
+    if allowed.is_empty() {
+        return;
+    }
+    let property = ToolInputSchemaProperty {
+        type_name: Some("string".to_owned()),
+        description: Some(format!(
+            "Tenant to query instead of the configured one: {}. Join several with '|' to query across tenants",
+            allowed.join(", ")
+        )),
+        enum_values: None,
+        items: None,
+        min_items: None,
+    };
+    for tool in tools.iter_mut().filter(|t| tool_accepts_tenant(&t.name)) {
+        tool.input_schema
+            .properties
+            .insert("tenant".to_string(), property.clone());
+        if let Some(parameters) = tool.parameters.as_mut() {
+            parameters
+                .properties
+                .insert("tenant".to_string(), property.clone());
+        }
+    }
+}
+
+/// Tool definitions for the Alertmanager API, listed only when an Alertmanager is configured
+fn alertmanager_tools() -> Vec<Tool> {
+    let alert_filter_schema = ToolInputSchema {
+        type_name: "object".to_string(),
+        properties: hashmap! {
+            "filter".to_string() => ToolInputSchemaProperty {
+                type_name: Some("array".to_owned()),
+                description: Some("Label matchers the alerts must match (e.g. ['alertname=\"HighLatency\"', 'severity=~\"page|critical\"'])".to_owned()),
+                enum_values: None,
+                items: Some(Box::new(ToolInputSchemaProperty {
+                    type_name: Some("string".to_owned()),
+                    enum_values: None,
+                    description: None,
+                    items: None,
+                    min_items: None,
+                })),
+                min_items: None,
+            },
+            "receiver".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("Regex matched against receiver names".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "active".to_string() => ToolInputSchemaProperty {
+                type_name: Some("boolean".to_owned()),
+                description: Some("Include active alerts (default: true)".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "silenced".to_string() => ToolInputSchemaProperty {
+                type_name: Some("boolean".to_owned()),
+                description: Some("Include silenced alerts (default: true)".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "inhibited".to_string() => ToolInputSchemaProperty {
+                type_name: Some("boolean".to_owned()),
+                description: Some("Include inhibited alerts (default: true)".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            }
+        },
+        required: vec![],
+        additional_properties: Some(false),
+    };
+
+    let status_schema = ToolInputSchema {
+        type_name: "object".to_string(),
+        properties: hashmap! {},
+        required: vec![],
+        additional_properties: Some(false),
+    };
+
+    let list_silences_schema = ToolInputSchema {
+        type_name: "object".to_string(),
+        properties: hashmap! {
+            "filter".to_string() => ToolInputSchemaProperty {
+                type_name: Some("array".to_owned()),
+                description: Some("Label matchers the silences must match (e.g. ['alertname=\"DiskFull\"'])".to_owned()),
+                enum_values: None,
+                items: Some(Box::new(ToolInputSchemaProperty {
+                    type_name: Some("string".to_owned()),
+                    enum_values: None,
+                    description: None,
+                    items: None,
+                    min_items: None,
+                })),
+                min_items: None,
+            },
+            "state".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("Only return silences in this state".to_owned()),
+                enum_values: Some(vec!["active".to_owned(), "pending".to_owned(), "expired".to_owned()]),
+                items: None,
+                min_items: None,
+            }
+        },
+        required: vec![],
+        additional_properties: Some(false),
+    };
+
+    vec![
+        Tool {
+            name: "alertmanager_list_alerts".to_string(),
+            description: Some(
+                "List alerts known to Alertmanager with their receivers and state (active, silenced by, inhibited by)"
+                    .to_string(),
+            ),
+            parameters: Some(alert_filter_schema.clone()),
+            input_schema: alert_filter_schema.clone(),
+            annotations: None,
+        },
+        Tool {
+            name: "alertmanager_list_alert_groups".to_string(),
+            description: Some(
+                "List Alertmanager alert groups: the grouping labels, the receiver they are routed to and their alerts"
+                    .to_string(),
+            ),
+            parameters: Some(alert_filter_schema.clone()),
+            input_schema: alert_filter_schema,
+            annotations: None,
+        },
+        Tool {
+            name: "alertmanager_get_status".to_string(),
+            description: Some(
+                "Get Alertmanager status: version, uptime, cluster state and peers, and the (redacted) configuration"
+                    .to_string(),
+            ),
+            parameters: Some(status_schema.clone()),
+            input_schema: status_schema,
+            annotations: None,
+        },
+        Tool {
+            name: "alertmanager_list_silences".to_string(),
+            description: Some(
+                "List Alertmanager silences with their matchers, state, time range, author and comment"
+                    .to_string(),
+            ),
+            parameters: Some(list_silences_schema.clone()),
+            input_schema: list_silences_schema,
+            annotations: None,
+        },
+    ]
+}
+
+/// Tool definitions for changing silences, listed only with `--enable-silence-tools`
+fn silence_tools() -> Vec<Tool> {
+    let dry_run_property = ToolInputSchemaProperty {
+        type_name: Some("boolean".to_owned()),
+        description: Some(
+            "Only preview the change and the affected alerts (default: true); set to false, with confirm, to apply"
+                .to_owned(),
+        ),
+        enum_values: None,
+        items: None,
+        min_items: None,
+    };
+    let confirm_property = ToolInputSchemaProperty {
+        type_name: Some("string".to_owned()),
+        description: Some(
+            "Confirmation token returned by the dry run of this exact change; required with dry_run: false"
+                .to_owned(),
+        ),
+        enum_values: None,
+        items: None,
+        min_items: None,
+    };
+    let id_property = ToolInputSchemaProperty {
+        type_name: Some("string".to_owned()),
+        description: Some("Silence ID".to_owned()),
+        enum_values: None,
+        items: None,
+        min_items: None,
+    };
+    let duration_property = |description: &str| ToolInputSchemaProperty {
+        type_name: Some("string".to_owned()),
+        description: Some(description.to_owned()),
+        enum_values: None,
+        items: None,
+        min_items: None,
+    };
+
+    let create_schema = ToolInputSchema {
+        type_name: "object".to_string(),
+        properties: hashmap! {
+            "matchers".to_string() => ToolInputSchemaProperty {
+                type_name: Some("array".to_owned()),
+                description: Some("Label matchers selecting the alerts to silence (e.g. ['alertname=\"DiskFull\"', 'instance=~\"db-.*\"'])".to_owned()),
+                enum_values: None,
+                items: Some(Box::new(ToolInputSchemaProperty {
+                    type_name: Some("string".to_owned()),
+                    enum_values: None,
+                    description: None,
+                    items: None,
+                    min_items: None,
+                })),
+                min_items: Some(1),
+            },
+            "duration".to_string() => duration_property("How long the silence lasts from now (e.g. 30m, 2h, 1h30m)"),
+            "comment".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("Why the alerts are silenced (e.g. incident or ticket reference)".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "created_by".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("Author recorded on the silence (default: prometheus-mcp)".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "dry_run".to_string() => dry_run_property.clone(),
+            "confirm".to_string() => confirm_property.clone()
+        },
+        required: vec![
+            "matchers".to_string(),
+            "duration".to_string(),
+            "comment".to_string(),
+        ],
+        additional_properties: Some(false),
+    };
+
+    let extend_schema = ToolInputSchema {
+        type_name: "object".to_string(),
+        properties: hashmap! {
+            "id".to_string() => id_property.clone(),
+            "duration".to_string() => duration_property("Time added to the current end of the silence (e.g. 1h)"),
+            "dry_run".to_string() => dry_run_property.clone(),
+            "confirm".to_string() => confirm_property.clone()
+        },
+        required: vec!["id".to_string(), "duration".to_string()],
+        additional_properties: Some(false),
+    };
+
+    let expire_schema = ToolInputSchema {
+        type_name: "object".to_string(),
+        properties: hashmap! {
+            "id".to_string() => id_property,
+            "dry_run".to_string() => dry_run_property,
+            "confirm".to_string() => confirm_property
+        },
+        required: vec!["id".to_string()],
+        additional_properties: Some(false),
+    };
+
+    vec![
+        Tool {
+            name: "alertmanager_create_silence".to_string(),
+            description: Some(
+                "Silence alerts matching the matchers for a duration. Dry run by default: previews the silence and the firing alerts it would mute"
+                    .to_string(),
+            ),
+            parameters: Some(create_schema.clone()),
+            input_schema: create_schema,
+            annotations: Some(ToolAnnotations {
+                title: Some("Create silence".to_string()),
+                read_only_hint: Some(false),
+                destructive_hint: Some(true),
+                idempotent_hint: Some(false),
+                open_world_hint: Some(false),
+            }),
+        },
+        Tool {
+            name: "alertmanager_extend_silence".to_string(),
+            description: Some(
+                "Extend an active or pending silence by a duration. Dry run by default: previews the new end time and the alerts it mutes"
+                    .to_string(),
+            ),
+            parameters: Some(extend_schema.clone()),
+            input_schema: extend_schema,
+            annotations: Some(ToolAnnotations {
+                title: Some("Extend silence".to_string()),
+                read_only_hint: Some(false),
+                destructive_hint: Some(true),
+                idempotent_hint: Some(false),
+                open_world_hint: Some(false),
+            }),
+        },
+        Tool {
+            name: "alertmanager_expire_silence".to_string(),
+            description: Some(
+                "Expire a silence now. Dry run by default: previews the alerts that would notify again"
+                    .to_string(),
+            ),
+            parameters: Some(expire_schema.clone()),
+            input_schema: expire_schema,
+            annotations: Some(ToolAnnotations {
+                title: Some("Expire silence".to_string()),
+                read_only_hint: Some(false),
+                destructive_hint: Some(true),
+                idempotent_hint: Some(true),
+                open_world_hint: Some(false),
+            }),
+        },
+    ]
+}
+
+/// Tool definitions for the TSDB admin API, listed only with `--enable-admin-tools`
+fn admin_tools() -> Vec<Tool> {
+    let dry_run_property = ToolInputSchemaProperty {
+        type_name: Some("boolean".to_owned()),
+        description: Some(
+            "Only report what would happen (default: true); set to false to execute".to_owned(),
+        ),
+        enum_values: None,
+        items: None,
+        min_items: None,
+    };
+
+    let snapshot_schema = ToolInputSchema {
+        type_name: "object".to_string(),
+        properties: hashmap! {
+            "skip_head".to_string() => ToolInputSchemaProperty {
+                type_name: Some("boolean".to_owned()),
+                description: Some("Skip data present in the head block (default: false)".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "dry_run".to_string() => dry_run_property.clone()
+        },
+        required: vec![],
+        additional_properties: Some(false),
+    };
+
+    let delete_series_schema = ToolInputSchema {
+        type_name: "object".to_string(),
+        properties: hashmap! {
+            "match_strings".to_string() => ToolInputSchemaProperty {
+                type_name: Some("array".to_owned()),
+                description: Some("Series selectors to delete (e.g. ['bad_metric{job=\"x\"}'])".to_owned()),
+                enum_values: None,
+                items: Some(Box::new(ToolInputSchemaProperty {
+                    type_name: Some("string".to_owned()),
+                    enum_values: None,
+                    description: None,
+                    items: None,
+                    min_items: None,
+                })),
+                min_items: Some(1),
+            },
+            "start".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("Start timestamp (RFC3339 or Unix timestamp); default: minimum possible time".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "end".to_string() => ToolInputSchemaProperty {
+                type_name: Some("string".to_owned()),
+                description: Some("End timestamp (RFC3339 or Unix timestamp); default: maximum possible time".to_owned()),
+                enum_values: None,
+                items: None,
+                min_items: None,
+            },
+            "dry_run".to_string() => dry_run_property.clone()
+        },
+        required: vec!["match_strings".to_string()],
+        additional_properties: Some(false),
+    };
+
+    let clean_tombstones_schema = ToolInputSchema {
+        type_name: "object".to_string(),
+        properties: hashmap! {
+            "dry_run".to_string() => dry_run_property
+        },
+        required: vec![],
+        additional_properties: Some(false),
+    };
+
+    vec![
+        Tool {
+            name: "prometheus_admin_snapshot".to_string(),
+            description: Some(
+                "Create a TSDB snapshot in the Prometheus data directory (admin API)".to_string(),
+            ),
+            parameters: Some(snapshot_schema.clone()),
+            input_schema: snapshot_schema,
+            annotations: Some(ToolAnnotations {
+                title: Some("Create TSDB snapshot".to_string()),
+                read_only_hint: Some(false),
+                destructive_hint: Some(true),
+                idempotent_hint: Some(false),
+                open_world_hint: Some(false),
+            }),
+        },
+        Tool {
+            name: "prometheus_admin_delete_series".to_string(),
+            description: Some(
+                "Delete all data for series matching the selectors (admin API). Dry run by default: lists the affected series"
+                    .to_string(),
+            ),
+            parameters: Some(delete_series_schema.clone()),
+            input_schema: delete_series_schema,
+            annotations: Some(ToolAnnotations {
+                title: Some("Delete series".to_string()),
+                read_only_hint: Some(false),
+                destructive_hint: Some(true),
+                idempotent_hint: Some(true),
+                open_world_hint: Some(false),
+            }),
+        },
+        Tool {
+            name: "prometheus_admin_clean_tombstones".to_string(),
+            description: Some(
+                "Permanently remove deleted data from disk and clean up tombstones (admin API)"
+                    .to_string(),
+            ),
+            parameters: Some(clean_tombstones_schema.clone()),
+            input_schema: clean_tombstones_schema,
+            annotations: Some(ToolAnnotations {
+                title: Some("Clean tombstones".to_string()),
+                read_only_hint: Some(false),
+                destructive_hint: Some(true),
+                idempotent_hint: Some(true),
+                open_world_hint: Some(false),
+            }),
+        },
+    ]
+}
+
+#[derive(Deserialize, Serialize, RpcParams)]
+pub struct PrometheusQueryRequest {
+    pub query: String,
+    pub time: Option<String>,
+    pub stats: Option<bool>,
+    pub timeout: Option<String>,
+    pub limit: Option<u64>,
+    pub dedup: Option<bool>,
+    pub partial_response: Option<bool>,
+    pub max_source_resolution: Option<String>,
+    pub engine: Option<String>,
+}
+
+pub async fn prometheus_query(request: PrometheusQueryRequest) -> HandlerResult<CallToolResult> {
     metrics::record_tool_call("prometheus_query");
 
+    let options = QueryOptions {
+        stats: request.stats.unwrap_or(false),
+        timeout: request.timeout,
+        limit: request.limit,
+        dedup: request.dedup,
+        partial_response: request.partial_response,
+        max_source_resolution: request.max_source_resolution,
+        engine: request.engine,
+    };
+    if let Err(e) = check_flavor_options(&options) {
+        return Ok(error_result(e));
+    }
+    let repo = get_repository();
+    match repo
+        .query(&request.query, request.time.as_deref(), &options)
+        .await
+    {
+        Ok(result) => {
+            let result_json =
+                serde_json::to_string_pretty(&result).unwrap_or_else(|_| "{}".to_string());
+            let mut content = vec![CallToolResultContent::Text { text: result_json }];
+            content.extend(stats_content(&result));
+            content.extend(annotations_content(&result));
+            Ok(CallToolResult {
+                content,
+                is_error: false,
+            })
+        }
+        Err(err) => Ok(CallToolResult {
+            content: vec![CallToolResultContent::Text {
+                text: err_string(&err),
+            }],
+            is_error: true,
+        }),
+    }
+}
+
+#[derive(Deserialize, Serialize, RpcParams)]
+pub struct PrometheusQueryRangeRequest {
+    pub query: String,
+    pub start: String,
+    pub end: String,
+    pub step: String,
+    pub stats: Option<bool>,
+    pub timeout: Option<String>,
+    pub limit: Option<u64>,
+    pub dedup: Option<bool>,
+    pub partial_response: Option<bool>,
+    pub max_source_resolution: Option<String>,
+    pub engine: Option<String>,
+}
+
+pub async fn prometheus_query_range(
+    request: PrometheusQueryRangeRequest,
+) -> HandlerResult<CallToolResult> {
+    metrics::record_tool_call("prometheus_query_range");
+
+    let options = QueryOptions {
+        stats: request.stats.unwrap_or(false),
+        timeout: request.timeout,
+        limit: request.limit,
+        dedup: request.dedup,
+        partial_response: request.partial_response,
+        max_source_resolution: request.max_source_resolution,
+        engine: request.engine,
+    };
+    if let Err(e) = check_flavor_options(&options) {
+        return Ok(error_result(e));
+    }
+    let repo = get_repository();
+    match repo
+        .query_range(
+            &request.query,
+            &request.start,
+            &request.end,
+            &request.step,
+            &options,
+        )
+        .await
+    {
+        Ok(result) => {
+            let result_json =
+                serde_json::to_string_pretty(&result).unwrap_or_else(|_| "{}".to_string());
+            let mut content = vec![CallToolResultContent::Text { text: result_json }];
+            content.extend(stats_content(&result));
+            content.extend(annotations_content(&result));
+            Ok(CallToolResult {
+                content,
+                is_error: false,
+            })
+        }
+        Err(err) => Ok(CallToolResult {
+            content: vec![CallToolResultContent::Text {
+                text: err_string(&err),
+            }],
+            is_error: true,
+        }),
+    }
+}
+
+#[derive(Deserialize, Serialize, RpcParams)]
+pub struct PrometheusListMetricsRequest {}
+
+pub async fn prometheus_list_metrics(
+    _request: PrometheusListMetricsRequest,
+) -> HandlerResult<CallToolResult> {
+    metrics::record_tool_call("prometheus_list_metrics");
+
+    let repo = get_repository();
+    match repo.list_metrics().await {
+        Ok(metrics) => {
+            let result_json =
+                serde_json::to_string_pretty(&metrics).unwrap_or_else(|_| "[]".to_string());
+            Ok(CallToolResult {
+                content: vec![CallToolResultContent::Text { text: result_json }],
+                is_error: false,
+            })
+        }
+        Err(err) => Ok(CallToolResult {
+            content: vec![CallToolResultContent::Text {
+                text: err_string(&err),
+            }],
+            is_error: true,
+        }),
+    }
+}
+
+#[derive(Deserialize, Serialize, RpcParams)]
+pub struct PrometheusGetMetadataRequest {
+    pub metric: Option<String>,
+    pub limit: Option<u64>,
+    pub limit_per_metric: Option<u64>,
+}
+
+pub async fn prometheus_get_metadata(
+    request: PrometheusGetMetadataRequest,
+) -> HandlerResult<CallToolResult> {
+    metrics::record_tool_call("prometheus_get_metadata");
+
+    let repo = get_repository();
+    match repo
+        .get_metadata(
+            request.metric.as_deref(),
+            request.limit,
+            request.limit_per_metric,
+        )
+        .await
+    {
+        Ok(metadata) => {
+            let result_json =
+                serde_json::to_string_pretty(&metadata).unwrap_or_else(|_| "[]".to_string());
+            Ok(CallToolResult {
+                content: vec![CallToolResultContent::Text { text: result_json }],
+                is_error: false,
+            })
+        }
+        Err(err) => Ok(CallToolResult {
+            content: vec![CallToolResultContent::Text {
+                text: err_string(&err),
+            }],
+            is_error: true,
+        }),
+    }
+}
+
+#[derive(Deserialize, Serialize, RpcParams)]
+pub struct PrometheusGetTargetsMetadataRequest {
+    pub match_target: Option<String>,
+    pub metric: Option<String>,
+    pub limit: Option<u64>,
+}
+
+pub async fn prometheus_get_targets_metadata(
+    request: PrometheusGetTargetsMetadataRequest,
+) -> HandlerResult<CallToolResult> {
+    metrics::record_tool_call("prometheus_get_targets_metadata");
+
+    let repo = get_repository();
+    match repo
+        .get_targets_metadata(
+            request.match_target.as_deref(),
+            request.metric.as_deref(),
+            request.limit,
+        )
+        .await
+    {
+        Ok(metadata) => {
+            let result_json =
+                serde_json::to_string_pretty(&metadata).unwrap_or_else(|_| "[]".to_string());
+            Ok(CallToolResult {
+                content: vec![CallToolResultContent::Text { text: result_json }],
+                is_error: false,
+            })
+        }
+        Err(err) => Ok(CallToolResult {
+            content: vec![CallToolResultContent::Text {
+                text: err_string(&err),
+            }],
+            is_error: true,
+        }),
+    }
+}
+
+#[derive(Deserialize, Serialize, RpcParams)]
+pub struct PrometheusGetSeriesRequest {
+    pub match_strings: Vec<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub limit: Option<u64>,
+}
+
+pub async fn prometheus_get_series(
+    request: PrometheusGetSeriesRequest,
+) -> HandlerResult<CallToolResult> {
+    metrics::record_tool_call("prometheus_get_series");
+
+    let repo = get_repository();
+
+    // Convert Vec<String> to Vec<&str>
+    let match_strings: Vec<&str> = request.match_strings.iter().map(|s| s.as_str()).collect();
+
+    match repo
+        .get_series(
+            match_strings,
+            request.start.as_deref(),
+            request.end.as_deref(),
+            request.limit,
+        )
+        .await
+    {
+        Ok(result) => {
+            let result_json =
+                serde_json::to_string_pretty(&result.series).unwrap_or_else(|_| "[]".to_string());
+            let mut content = vec![CallToolResultContent::Text { text: result_json }];
+            if result.truncated {
+                content.push(CallToolResultContent::Text {
+                    text: format!(
+                        "Result truncated to {} series; narrow the selectors or time range, or raise `limit`.",
+                        result.series.len()
+                    ),
+                });
+            }
+            Ok(CallToolResult {
+                content,
+                is_error: false,
+            })
+        }
+        Err(err) => Ok(CallToolResult {
+            content: vec![CallToolResultContent::Text {
+                text: err_string(&err),
+            }],
+            is_error: true,
+        }),
+    }
+}
+
+#[derive(Deserialize, Serialize, RpcParams)]
+pub struct PrometheusGetLabelValuesRequest {
+    pub label_name: String,
+    pub match_strings: Option<Vec<String>>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub limit: Option<u64>,
+}
+
+pub async fn prometheus_get_label_values(
+    request: PrometheusGetLabelValuesRequest,
+) -> HandlerResult<CallToolResult> {
+    metrics::record_tool_call("prometheus_get_label_values");
+
+    let repo = get_repository();
+    match repo
+        .get_label_values(
+            &request.label_name,
+            as_str_vec(&request.match_strings),
+            request.start.as_deref(),
+            request.end.as_deref(),
+            request.limit,
+        )
+        .await
+    {
+        Ok(values) => {
+            let result_json =
+                serde_json::to_string_pretty(&values).unwrap_or_else(|_| "[]".to_string());
+            Ok(CallToolResult {
+                content: vec![CallToolResultContent::Text { text: result_json }],
+                is_error: false,
+            })
+        }
+        Err(err) => Ok(CallToolResult {
+            content: vec![CallToolResultContent::Text {
+                text: err_string(&err),
+            }],
+            is_error: true,
+        }),
+    }
+}
+
+#[derive(Deserialize, Serialize, RpcParams)]
+pub struct PrometheusListLabelsRequest {
+    pub match_strings: Option<Vec<String>>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub limit: Option<u64>,
+}
+
+pub async fn prometheus_list_labels(
+    request: PrometheusListLabelsRequest,
+) -> HandlerResult<CallToolResult> {
+    metrics::record_tool_call("prometheus_list_labels");
+
+    let repo = get_repository();
+    match repo
+        .list_labels(
+            as_str_vec(&request.match_strings),
+            request.start.as_deref(),
+            request.end.as_deref(),
+            request.limit,
+        )
+        .await
+    {
+        Ok(labels) => {
+            let result_json =
+                serde_json::to_string_pretty(&labels).unwrap_or_else(|_| "[]".to_string());
+            Ok(CallToolResult {
+                content: vec![CallToolResultContent::Text { text: result_json }],
+                is_error: false,
+            })
+        }
+        Err(err) => Ok(CallToolResult {
+            content: vec![CallToolResultContent::Text {
+                text: err_string(&err),
+            }],
+            is_error: true,
+        }),
+    }
+}
+
+#[derive(Deserialize, Serialize, RpcParams)]
+pub struct PrometheusGetAlertsRequest {
+    pub state: Option<String>,
+    pub alertname: Option<String>,
+    pub matchers: Option<Vec<String>>,
+}
+
+pub async fn prometheus_get_alerts(
+    request: PrometheusGetAlertsRequest,
+) -> HandlerResult<CallToolResult> {
+    metrics::record_tool_call("prometheus_get_alerts");
+
+    let mut matchers: Vec<LabelMatcher> = Vec::new();
+    for m in request.matchers.iter().flatten() {
+        match parse_matchers(m) {
+            Ok(parsed) => matchers.extend(parsed),
+            Err(err) => {
+                return Ok(CallToolResult {
+                    content: vec![CallToolResultContent::Text {
+                        text: format!("Invalid matcher: {}", err),
+                    }],
+                    is_error: true,
+                })
+            }
+        }
+    }
+
+    let repo = get_repository();
+    match repo.get_alerts().await {
+        Ok(alerts) => {
+            let alerts = filter_alerts(
+                alerts,
+                request.state.as_deref(),
+                request.alertname.as_deref(),
+                &matchers,
+            );
+            let result_json =
+                serde_json::to_string_pretty(&alerts).unwrap_or_else(|_| "[]".to_string());
+            Ok(CallToolResult {
+                content: vec![CallToolResultContent::Text { text: result_json }],
+                is_error: false,
+            })
+        }
+        Err(err) => Ok(CallToolResult {
+            content: vec![CallToolResultContent::Text {
+                text: err_string(&err),
+            }],
+            is_error: true,
+        }),
+    }
+}
+
+#[derive(Deserialize, Serialize, RpcParams)]
+pub struct PrometheusGetRulesRequest {
+    #[serde(rename = "type")]
+    pub rule_type: Option<String>,
+    pub rule_name: Option<Vec<String>>,
+    pub rule_group: Option<Vec<String>>,
+    pub file: Option<Vec<String>>,
+}
+
+pub async fn prometheus_get_rules(
+    request: PrometheusGetRulesRequest,
+) -> HandlerResult<CallToolResult> {
+    metrics::record_tool_call("prometheus_get_rules");
+
+    let repo = get_repository();
+
+    match repo
+        .get_rules(
+            request.rule_type.as_deref(),
+            as_str_vec(&request.rule_name),
+            as_str_vec(&request.rule_group),
+            as_str_vec(&request.file),
+        )
+        .await
+    {
+        Ok(groups) => {
+            let result_json =
+                serde_json::to_string_pretty(&groups).unwrap_or_else(|_| "[]".to_string());
+            Ok(CallToolResult {
+                content: vec![CallToolResultContent::Text { text: result_json }],
+                is_error: false,
+            })
+        }
+        Err(err) => Ok(CallToolResult {
+            content: vec![CallToolResultContent::Text {
+                text: err_string(&err),
+            }],
+            is_error: true,
+        }),
+    }
+}
+
+#[derive(Deserialize, Serialize, RpcParams)]
+pub struct PrometheusGetStatusRequest {
+    pub section: String,
+}
+
+pub async fn prometheus_get_status(
+    request: PrometheusGetStatusRequest,
+) -> HandlerResult<CallToolResult> {
+    metrics::record_tool_call("prometheus_get_status");
+
+    let section = match request.section.parse::<StatusSection>() {
+        Ok(section) => section,
+        Err(err) => {
+            return Ok(CallToolResult {
+                content: vec![CallToolResultContent::Text { text: err }],
+                is_error: true,
+            })
+        }
+    };
+
+    let repo = get_repository();
+    match repo.get_status(section).await {
+        Ok(mut status) => {
+            // Never hand the raw configuration to the model
+            if section == StatusSection::Config {
+                if let Some(yaml) = status.get_mut("yaml") {
+                    if let Some(text) = yaml.as_str() {
+                        *yaml = serde_json::Value::String(redact_config(text));
+                    }
+                }
+            }
+            let result_json =
+                serde_json::to_string_pretty(&status).unwrap_or_else(|_| "{}".to_string());
+            Ok(CallToolResult {
+                content: vec![CallToolResultContent::Text { text: result_json }],
+                is_error: false,
+            })
+        }
+        Err(err) => Ok(CallToolResult {
+            content: vec![CallToolResultContent::Text {
+                text: err_string(&err),
+            }],
+            is_error: true,
+        }),
+    }
+}
+
+#[derive(Deserialize, Serialize, RpcParams)]
+pub struct PrometheusTsdbStatusRequest {
+    pub limit: Option<u64>,
+}
+
+pub async fn prometheus_tsdb_status(
+    request: PrometheusTsdbStatusRequest,
+) -> HandlerResult<CallToolResult> {
+    metrics::record_tool_call("prometheus_tsdb_status");
+
+    let repo = get_repository();
+    match repo.get_tsdb_status(request.limit).await {
+        Ok(status) => {
+            let result_json =
+                serde_json::to_string_pretty(&status).unwrap_or_else(|_| "{}".to_string());
+            Ok(CallToolResult {
+                content: vec![CallToolResultContent::Text { text: result_json }],
+                is_error: false,
+            })
+        }
+        Err(err) => Ok(CallToolResult {
+            content: vec![CallToolResultContent::Text {
+                text: err_string(&err),
+            }],
+            is_error: true,
+        }),
+    }
+}
+
+#[derive(Deserialize, Serialize, RpcParams)]
+pub struct PrometheusQueryExemplarsRequest {
+    pub query: String,
+    pub start: Option<String>,
+    pub end: Option<String>,
+}
+
+pub async fn prometheus_query_exemplars(
+    request: PrometheusQueryExemplarsRequest,
+) -> HandlerResult<CallToolResult> {
+    metrics::record_tool_call("prometheus_query_exemplars");
+
+    let repo = get_repository();
+    match repo
+        .query_exemplars(
+            &request.query,
+            request.start.as_deref(),
+            request.end.as_deref(),
+        )
+        .await
+    {
+        Ok(exemplars) => {
+            let result_json =
+                serde_json::to_string_pretty(&exemplars).unwrap_or_else(|_| "[]".to_string());
+            Ok(CallToolResult {
+                content: vec![CallToolResultContent::Text { text: result_json }],
+                is_error: false,
+            })
+        }
+        Err(err) => Ok(CallToolResult {
+            content: vec![CallToolResultContent::Text {
+                text: err_string(&err),
+            }],
+            is_error: true,
+        }),
+    }
+}
+
+#[derive(Deserialize, Serialize, RpcParams)]
+pub struct PrometheusFormatQueryRequest {
+    pub query: String,
+}
+
+pub async fn prometheus_format_query(
+    request: PrometheusFormatQueryRequest,
+) -> HandlerResult<CallToolResult> {
+    metrics::record_tool_call("prometheus_format_query");
+
+    let repo = get_repository();
+    match repo.format_query(&request.query).await {
+        Ok(formatted) => Ok(CallToolResult {
+            content: vec![CallToolResultContent::Text { text: formatted }],
+            is_error: false,
+        }),
+        Err(err) => Ok(CallToolResult {
+            content: vec![CallToolResultContent::Text {
+                text: err_string(&err),
+            }],
+            is_error: true,
+        }),
+    }
+}
+
+#[derive(Deserialize, Serialize, RpcParams)]
+pub struct PrometheusParseQueryRequest {
+    pub query: String,
+}
+
+pub async fn prometheus_parse_query(
+    request: PrometheusParseQueryRequest,
+) -> HandlerResult<CallToolResult> {
+    metrics::record_tool_call("prometheus_parse_query");
+
     let repo = get_repository();
-    match repo.query(&request.query, request.time.as_deref()).await {
-        Ok(result) => {
+    match repo.parse_query(&request.query).await {
+        Ok(ast) => {
             let result_json =
-                serde_json::to_string_pretty(&result).unwrap_or_else(|_| "{}".to_string());
+                serde_json::to_string_pretty(&ast).unwrap_or_else(|_| "{}".to_string());
             Ok(CallToolResult {
                 content: vec![CallToolResultContent::Text { text: result_json }],
                 is_error: false,
@@ -227,26 +2033,34 @@ pub async fn prometheus_query(request: PrometheusQueryRequest) -> HandlerResult<
 }
 
 #[derive(Deserialize, Serialize, RpcParams)]
-pub struct PrometheusQueryRangeRequest {
-    pub query: String,
+pub struct PrometheusReadRawRequest {
+    pub selector: String,
     pub start: String,
     pub end: String,
-    pub step: String,
 }
 
-pub async fn prometheus_query_range(
-    request: PrometheusQueryRangeRequest,
+pub async fn prometheus_read_raw(
+    request: PrometheusReadRawRequest,
 ) -> HandlerResult<CallToolResult> {
-    metrics::record_tool_call("prometheus_query_range");
+    metrics::record_tool_call("prometheus_read_raw");
+
+    let range = parse_timestamp_ms(&request.start)
+        .and_then(|start| Ok((start, parse_timestamp_ms(&request.end)?)));
+    let (start_ms, end_ms) = match range {
+        Ok(range) => range,
+        Err(e) => {
+            return Ok(CallToolResult {
+                content: vec![CallToolResultContent::Text { text: e }],
+                is_error: true,
+            })
+        }
+    };
 
     let repo = get_repository();
-    match repo
-        .query_range(&request.query, &request.start, &request.end, &request.step)
-        .await
-    {
-        Ok(result) => {
+    match repo.read_raw(&request.selector, start_ms, end_ms).await {
+        Ok(series) => {
             let result_json =
-                serde_json::to_string_pretty(&result).unwrap_or_else(|_| "{}".to_string());
+                serde_json::to_string_pretty(&series).unwrap_or_else(|_| "[]".to_string());
             Ok(CallToolResult {
                 content: vec![CallToolResultContent::Text { text: result_json }],
                 is_error: false,
@@ -262,18 +2076,21 @@ pub async fn prometheus_query_range(
 }
 
 #[derive(Deserialize, Serialize, RpcParams)]
-pub struct PrometheusListMetricsRequest {}
+pub struct PrometheusFederateRequest {
+    pub match_strings: Vec<String>,
+}
 
-pub async fn prometheus_list_metrics(
-    _request: PrometheusListMetricsRequest,
+pub async fn prometheus_federate(
+    request: PrometheusFederateRequest,
 ) -> HandlerResult<CallToolResult> {
-    metrics::record_tool_call("prometheus_list_metrics");
+    metrics::record_tool_call("prometheus_federate");
 
     let repo = get_repository();
-    match repo.list_metrics().await {
-        Ok(metrics) => {
+    let match_strings: Vec<&str> = request.match_strings.iter().map(|s| s.as_str()).collect();
+    match repo.federate(match_strings).await {
+        Ok(families) => {
             let result_json =
-                serde_json::to_string_pretty(&metrics).unwrap_or_else(|_| "[]".to_string());
+                serde_json::to_string_pretty(&families).unwrap_or_else(|_| "[]".to_string());
             Ok(CallToolResult {
                 content: vec![CallToolResultContent::Text { text: result_json }],
                 is_error: false,
@@ -289,20 +2106,31 @@ pub async fn prometheus_list_metrics(
 }
 
 #[derive(Deserialize, Serialize, RpcParams)]
-pub struct PrometheusGetMetadataRequest {
-    pub metric: String,
+pub struct PrometheusScrapeTargetRequest {
+    pub url: String,
+    pub metric: Option<String>,
+    pub include_samples: Option<bool>,
 }
 
-pub async fn prometheus_get_metadata(
-    request: PrometheusGetMetadataRequest,
+pub async fn prometheus_scrape_target(
+    request: PrometheusScrapeTargetRequest,
 ) -> HandlerResult<CallToolResult> {
-    metrics::record_tool_call("prometheus_get_metadata");
+    metrics::record_tool_call("prometheus_scrape_target");
 
-    let repo = get_repository();
-    match repo.get_metadata(&request.metric).await {
-        Ok(metadata) => {
+    if let Err(err) = scrape::check_allowed_host(&request.url, &scrape_allowed_hosts()) {
+        return Ok(error_result(err_string(&err)));
+    }
+    match scrape::scrape(&request.url, scrape_timeout()).await {
+        Ok((format, families)) => {
+            let report = scrape::summarize(
+                &request.url,
+                format,
+                families,
+                request.metric.as_deref(),
+                request.include_samples.unwrap_or(false),
+            );
             let result_json =
-                serde_json::to_string_pretty(&metadata).unwrap_or_else(|_| "[]".to_string());
+                serde_json::to_string_pretty(&report).unwrap_or_else(|_| "{}".to_string());
             Ok(CallToolResult {
                 content: vec![CallToolResultContent::Text { text: result_json }],
                 is_error: false,
@@ -318,29 +2146,114 @@ pub async fn prometheus_get_metadata(
 }
 
 #[derive(Deserialize, Serialize, RpcParams)]
-pub struct PrometheusGetSeriesRequest {
+pub struct PrometheusAdminSnapshotRequest {
+    pub skip_head: Option<bool>,
+    pub dry_run: Option<bool>,
+}
+
+pub async fn prometheus_admin_snapshot(
+    request: PrometheusAdminSnapshotRequest,
+) -> HandlerResult<CallToolResult> {
+    metrics::record_tool_call("prometheus_admin_snapshot");
+
+    if let Some(disabled) = admin_tools_disabled() {
+        return Ok(disabled);
+    }
+    let skip_head = request.skip_head.unwrap_or(false);
+    if request.dry_run.unwrap_or(true) {
+        return Ok(json_result(&json!({
+            "dry_run": true,
+            "action": "snapshot",
+            "skip_head": skip_head,
+            "note": "Would create a snapshot under <data-dir>/snapshots; no series are modified",
+        })));
+    }
+
+    let repo = get_repository();
+    match repo.snapshot(skip_head).await {
+        Ok(name) => Ok(json_result(&json!({
+            "dry_run": false,
+            "action": "snapshot",
+            "name": name,
+        }))),
+        Err(err) => Ok(CallToolResult {
+            content: vec![CallToolResultContent::Text {
+                text: err_string(&err),
+            }],
+            is_error: true,
+        }),
+    }
+}
+
+#[derive(Deserialize, Serialize, RpcParams)]
+pub struct PrometheusAdminDeleteSeriesRequest {
     pub match_strings: Vec<String>,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub dry_run: Option<bool>,
 }
 
-pub async fn prometheus_get_series(
-    request: PrometheusGetSeriesRequest,
+pub async fn prometheus_admin_delete_series(
+    request: PrometheusAdminDeleteSeriesRequest,
 ) -> HandlerResult<CallToolResult> {
-    metrics::record_tool_call("prometheus_get_series");
+    metrics::record_tool_call("prometheus_admin_delete_series");
+
+    if let Some(disabled) = admin_tools_disabled() {
+        return Ok(disabled);
+    }
+    if request.match_strings.is_empty() {
+        return Ok(CallToolResult {
+            content: vec![CallToolResultContent::Text {
+                text: "At least one series selector is required".to_string(),
+            }],
+            is_error: true,
+        });
+    }
 
     let repo = get_repository();
+    let match_strings: Vec<&str> = request.match_strings.iter().map(String::as_str).collect();
+    let start = request.start.as_deref();
+    let end = request.end.as_deref();
 
-    // Convert Vec<String> to Vec<&str>
-    let match_strings: Vec<&str> = request.match_strings.iter().map(|s| s.as_str()).collect();
+    if request.dry_run.unwrap_or(true) {
+        return match repo
+            .get_series(
+                match_strings.clone(),
+                start,
+                end,
+                Some(DRY_RUN_SERIES_LIMIT),
+            )
+            .await
+        {
+            Ok(found) => Ok(json_result(&json!({
+                "dry_run": true,
+                "action": "delete_series",
+                "match": request.match_strings,
+                "start": request.start,
+                "end": request.end,
+                // Capped at DRY_RUN_SERIES_LIMIT; `truncated` means more series match
+                "series_listed": found.series.len(),
+                "truncated": found.truncated,
+                "series": found.series,
+            }))),
+            Err(err) => Ok(CallToolResult {
+                content: vec![CallToolResultContent::Text {
+                    text: err_string(&err),
+                }],
+                is_error: true,
+            }),
+        };
+    }
 
-    match repo.get_series(match_strings).await {
-        Ok(series) => {
-            let result_json =
-                serde_json::to_string_pretty(&series).unwrap_or_else(|_| "[]".to_string());
-            Ok(CallToolResult {
-                content: vec![CallToolResultContent::Text { text: result_json }],
-                is_error: false,
-            })
-        }
+    match repo.delete_series(match_strings, start, end).await {
+        Ok(()) => Ok(json_result(&json!({
+            "dry_run": false,
+            "action": "delete_series",
+            "match": request.match_strings,
+            "start": request.start,
+            "end": request.end,
+            "note": "Series marked for deletion; run prometheus_admin_clean_tombstones to free disk space",
+        }))),
         Err(err) => Ok(CallToolResult {
             content: vec![CallToolResultContent::Text {
                 text: err_string(&err),
@@ -351,25 +2264,32 @@ pub async fn prometheus_get_series(
 }
 
 #[derive(Deserialize, Serialize, RpcParams)]
-pub struct PrometheusGetLabelValuesRequest {
-    pub label_name: String,
+pub struct PrometheusAdminCleanTombstonesRequest {
+    pub dry_run: Option<bool>,
 }
 
-pub async fn prometheus_get_label_values(
-    request: PrometheusGetLabelValuesRequest,
+pub async fn prometheus_admin_clean_tombstones(
+    request: PrometheusAdminCleanTombstonesRequest,
 ) -> HandlerResult<CallToolResult> {
-    metrics::record_tool_call("prometheus_get_label_values");
+    metrics::record_tool_call("prometheus_admin_clean_tombstones");
+
+    if let Some(disabled) = admin_tools_disabled() {
+        return Ok(disabled);
+    }
+    if request.dry_run.unwrap_or(true) {
+        return Ok(json_result(&json!({
+            "dry_run": true,
+            "action": "clean_tombstones",
+            "note": "Would permanently remove data of previously deleted series from disk",
+        })));
+    }
 
     let repo = get_repository();
-    match repo.get_label_values(&request.label_name).await {
-        Ok(values) => {
-            let result_json =
-                serde_json::to_string_pretty(&values).unwrap_or_else(|_| "[]".to_string());
-            Ok(CallToolResult {
-                content: vec![CallToolResultContent::Text { text: result_json }],
-                is_error: false,
-            })
-        }
+    match repo.clean_tombstones().await {
+        Ok(()) => Ok(json_result(&json!({
+            "dry_run": false,
+            "action": "clean_tombstones",
+        }))),
         Err(err) => Ok(CallToolResult {
             content: vec![CallToolResultContent::Text {
                 text: err_string(&err),
@@ -379,6 +2299,543 @@ pub async fn prometheus_get_label_values(
     }
 }
 
-fn err_string(err: &dyn std::fmt::Debug) -> String {
-    format!("{:?}", err)
+#[derive(Deserialize, Serialize, RpcParams)]
+pub struct AlertmanagerAlertFilterRequest {
+    pub filter: Option<Vec<String>>,
+    pub receiver: Option<String>,
+    pub active: Option<bool>,
+    pub silenced: Option<bool>,
+    pub inhibited: Option<bool>,
+}
+
+impl AlertmanagerAlertFilterRequest {
+    fn to_filter(&self) -> AlertFilter {
+        AlertFilter {
+            filter: self.filter.clone().unwrap_or_default(),
+            receiver: self.receiver.clone(),
+            active: self.active,
+            silenced: self.silenced,
+            inhibited: self.inhibited,
+        }
+    }
+}
+
+pub async fn alertmanager_list_alerts(
+    request: AlertmanagerAlertFilterRequest,
+) -> HandlerResult<CallToolResult> {
+    metrics::record_tool_call("alertmanager_list_alerts");
+
+    let Some(am) = get_alertmanager() else {
+        return Ok(alertmanager_not_configured());
+    };
+    match am.get_alerts(&request.to_filter()).await {
+        Ok(alerts) => Ok(json_result(&alerts)),
+        Err(err) => Ok(error_result(err_string(&err))),
+    }
+}
+
+pub async fn alertmanager_list_alert_groups(
+    request: AlertmanagerAlertFilterRequest,
+) -> HandlerResult<CallToolResult> {
+    metrics::record_tool_call("alertmanager_list_alert_groups");
+
+    let Some(am) = get_alertmanager() else {
+        return Ok(alertmanager_not_configured());
+    };
+    match am.get_alert_groups(&request.to_filter()).await {
+        Ok(groups) => Ok(json_result(&groups)),
+        Err(err) => Ok(error_result(err_string(&err))),
+    }
+}
+
+#[derive(Deserialize, Serialize, RpcParams)]
+pub struct AlertmanagerGetStatusRequest {}
+
+pub async fn alertmanager_get_status(
+    _request: AlertmanagerGetStatusRequest,
+) -> HandlerResult<CallToolResult> {
+    metrics::record_tool_call("alertmanager_get_status");
+
+    let Some(am) = get_alertmanager() else {
+        return Ok(alertmanager_not_configured());
+    };
+    match am.get_status().await {
+        Ok(mut status) => {
+            // Alertmanager masks its own secret fields; also scrub anything else that looks sensitive
+            status.config.original = redact_config(&status.config.original);
+            Ok(json_result(&status))
+        }
+        Err(err) => Ok(error_result(err_string(&err))),
+    }
+}
+
+#[derive(Deserialize, Serialize, RpcParams)]
+pub struct AlertmanagerListSilencesRequest {
+    pub filter: Option<Vec<String>>,
+    pub state: Option<String>,
+}
+
+pub async fn alertmanager_list_silences(
+    request: AlertmanagerListSilencesRequest,
+) -> HandlerResult<CallToolResult> {
+    metrics::record_tool_call("alertmanager_list_silences");
+
+    let Some(am) = get_alertmanager() else {
+        return Ok(alertmanager_not_configured());
+    };
+    match am
+        .get_silences(request.filter.as_deref().unwrap_or_default())
+        .await
+    {
+        Ok(mut silences) => {
+            if let Some(state) = &request.state {
+                silences.retain(|s| s.state() == state);
+            }
+            Ok(json_result(&silences))
+        }
+        Err(err) => Ok(error_result(err_string(&err))),
+    }
+}
+
+#[derive(Deserialize, Serialize, RpcParams)]
+pub struct AlertmanagerCreateSilenceRequest {
+    pub matchers: Vec<String>,
+    pub duration: String,
+    pub comment: String,
+    pub created_by: Option<String>,
+    pub dry_run: Option<bool>,
+    pub confirm: Option<String>,
+}
+
+pub async fn alertmanager_create_silence(
+    request: AlertmanagerCreateSilenceRequest,
+) -> HandlerResult<CallToolResult> {
+    metrics::record_tool_call("alertmanager_create_silence");
+
+    if let Some(disabled) = silence_tools_disabled() {
+        return Ok(disabled);
+    }
+    let Some(am) = get_alertmanager() else {
+        return Ok(alertmanager_not_configured());
+    };
+
+    let matchers = match parse_silence_matchers(&request.matchers) {
+        Ok(matchers) => matchers,
+        Err(e) => return Ok(error_result(e)),
+    };
+    let duration_ms = match parse_duration_ms(&request.duration) {
+        Ok(ms) if ms > 0 => ms,
+        Ok(_) => return Ok(error_result("Duration must be positive".to_string())),
+        Err(e) => return Ok(error_result(e)),
+    };
+    if request.comment.trim().is_empty() {
+        return Ok(error_result(
+            "A comment explaining the silence is required".to_string(),
+        ));
+    }
+
+    let now = now_ms();
+    let silence = Silence {
+        matchers,
+        starts_at: format_rfc3339_ms(now),
+        ends_at: format_rfc3339_ms(now + duration_ms),
+        created_by: request
+            .created_by
+            .unwrap_or_else(|| "prometheus-mcp".to_string()),
+        comment: request.comment,
+        ..Default::default()
+    };
+
+    let matchers: Vec<String> = silence.matchers.iter().map(ToString::to_string).collect();
+    let token = confirmation_token(&[
+        "create_silence",
+        &matchers.join(","),
+        &request.duration,
+        &silence.comment,
+        &silence.created_by,
+    ]);
+
+    // The preview is part of every response so the caller always sees what gets muted
+    let preview = match silence_preview(am.as_ref(), &silence.matchers, None).await {
+        Ok(preview) => preview,
+        Err(err) => return Ok(error_result(err_string(&err))),
+    };
+    if request.dry_run.unwrap_or(true) {
+        return Ok(json_result(&json!({
+            "dry_run": true,
+            "action": "create_silence",
+            "silence": silence,
+            "matching_alerts": preview,
+            "confirm": token,
+            "note": "Review the matching alerts, then call again with dry_run: false and this confirm token to create the silence",
+        })));
+    }
+    if let Some(unconfirmed) = unconfirmed_silence_change(request.confirm.as_deref(), &token) {
+        return Ok(unconfirmed);
+    }
+
+    match am.post_silence(&silence).await {
+        Ok(id) => Ok(json_result(&json!({
+            "dry_run": false,
+            "action": "create_silence",
+            "silence_id": id,
+            "silence": silence,
+            "matching_alerts": preview,
+        }))),
+        Err(err) => Ok(error_result(err_string(&err))),
+    }
+}
+
+#[derive(Deserialize, Serialize, RpcParams)]
+pub struct AlertmanagerExtendSilenceRequest {
+    pub id: String,
+    pub duration: String,
+    pub dry_run: Option<bool>,
+    pub confirm: Option<String>,
+}
+
+pub async fn alertmanager_extend_silence(
+    request: AlertmanagerExtendSilenceRequest,
+) -> HandlerResult<CallToolResult> {
+    metrics::record_tool_call("alertmanager_extend_silence");
+
+    if let Some(disabled) = silence_tools_disabled() {
+        return Ok(disabled);
+    }
+    let Some(am) = get_alertmanager() else {
+        return Ok(alertmanager_not_configured());
+    };
+    let duration_ms = match parse_duration_ms(&request.duration) {
+        Ok(ms) if ms > 0 => ms,
+        Ok(_) => return Ok(error_result("Duration must be positive".to_string())),
+        Err(e) => return Ok(error_result(e)),
+    };
+
+    let silence = match am.get_silence(&request.id).await {
+        Ok(silence) => silence,
+        Err(err) => return Ok(error_result(err_string(&err))),
+    };
+    if silence.state() == "expired" {
+        return Ok(error_result(format!(
+            "Silence {} has expired; create a new silence instead",
+            request.id
+        )));
+    }
+    let current_end = match parse_timestamp_ms(&silence.ends_at) {
+        Ok(ms) => ms,
+        Err(e) => return Ok(error_result(e)),
+    };
+    let updated = Silence {
+        ends_at: format_rfc3339_ms(current_end.max(now_ms()) + duration_ms),
+        ..silence.clone()
+    };
+
+    // Bound to the silence's current end, so a change made in between needs a new preview
+    let token = confirmation_token(&[
+        "extend_silence",
+        &request.id,
+        &silence.ends_at,
+        &request.duration,
+    ]);
+
+    let preview = match silence_preview(am.as_ref(), &silence.matchers, None).await {
+        Ok(preview) => preview,
+        Err(err) => return Ok(error_result(err_string(&err))),
+    };
+    if request.dry_run.unwrap_or(true) {
+        return Ok(json_result(&json!({
+            "dry_run": true,
+            "action": "extend_silence",
+            "id": request.id,
+            "previous_ends_at": silence.ends_at,
+            "ends_at": updated.ends_at,
+            "silence": updated,
+            "matching_alerts": preview,
+            "confirm": token,
+        })));
+    }
+    if let Some(unconfirmed) = unconfirmed_silence_change(request.confirm.as_deref(), &token) {
+        return Ok(unconfirmed);
+    }
+
+    match am.post_silence(&updated).await {
+        Ok(id) => Ok(json_result(&json!({
+            "dry_run": false,
+            "action": "extend_silence",
+            "silence_id": id,
+            "previous_ends_at": silence.ends_at,
+            "ends_at": updated.ends_at,
+        }))),
+        Err(err) => Ok(error_result(err_string(&err))),
+    }
+}
+
+#[derive(Deserialize, Serialize, RpcParams)]
+pub struct AlertmanagerExpireSilenceRequest {
+    pub id: String,
+    pub dry_run: Option<bool>,
+    pub confirm: Option<String>,
+}
+
+pub async fn alertmanager_expire_silence(
+    request: AlertmanagerExpireSilenceRequest,
+) -> HandlerResult<CallToolResult> {
+    metrics::record_tool_call("alertmanager_expire_silence");
+
+    if let Some(disabled) = silence_tools_disabled() {
+        return Ok(disabled);
+    }
+    let Some(am) = get_alertmanager() else {
+        return Ok(alertmanager_not_configured());
+    };
+
+    let silence = match am.get_silence(&request.id).await {
+        Ok(silence) => silence,
+        Err(err) => return Ok(error_result(err_string(&err))),
+    };
+    let token = confirmation_token(&["expire_silence", &request.id, &silence.ends_at]);
+
+    if request.dry_run.unwrap_or(true) {
+        let preview = match silence_preview(am.as_ref(), &silence.matchers, Some(&request.id)).await
+        {
+            Ok(preview) => preview,
+            Err(err) => return Ok(error_result(err_string(&err))),
+        };
+        return Ok(json_result(&json!({
+            "dry_run": true,
+            "action": "expire_silence",
+            "id": request.id,
+            "silence": silence,
+            "unsilenced_alerts": preview,
+            "confirm": token,
+            "note": "These alerts are muted by this silence and will notify again unless another silence or inhibition covers them",
+        })));
+    }
+    if let Some(unconfirmed) = unconfirmed_silence_change(request.confirm.as_deref(), &token) {
+        return Ok(unconfirmed);
+    }
+
+    match am.expire_silence(&request.id).await {
+        Ok(()) => Ok(json_result(&json!({
+            "dry_run": false,
+            "action": "expire_silence",
+            "id": request.id,
+        }))),
+        Err(err) => Ok(error_result(err_string(&err))),
+    }
+}
+
+/// Parse silence matchers; each entry may hold one or more comma-separated matchers
+fn parse_silence_matchers(inputs: &[String]) -> Result<Vec<SilenceMatcher>, String> {
+    let mut parsed = Vec::new();
+    for input in inputs {
+        parsed.extend(parse_matchers(input)?);
+    }
+    if parsed.is_empty() {
+        return Err("At least one matcher is required".to_string());
+    }
+    // Same rule as Alertmanager: a silence whose matchers all match an empty label would
+    // mute every alert
+    if parsed.iter().all(|m| m.matches(&HashMap::new())) {
+        return Err("At least one matcher must not match the empty string".to_string());
+    }
+    Ok(parsed.iter().map(SilenceMatcher::from).collect())
+}
+
+/// Alerts currently known to Alertmanager that `matchers` select. Firing alerts are listed
+/// apart from those a silence or inhibition already mutes; with `silenced_by`, only the
+/// alerts muted by that silence are listed.
+async fn silence_preview(
+    am: &dyn AlertmanagerRepository,
+    matchers: &[SilenceMatcher],
+    silenced_by: Option<&str>,
+) -> Result<serde_json::Value, PrometheusError> {
+    let filter = AlertFilter {
+        filter: matchers.iter().map(ToString::to_string).collect(),
+        ..Default::default()
+    };
+    let mut alerts = am.get_alerts(&filter).await?;
+    if let Some(id) = silenced_by {
+        alerts.retain(|a| a.status.silenced_by.iter().any(|s| s == id));
+        return Ok(preview_alerts(&alerts));
+    }
+    let (muted, firing): (Vec<_>, Vec<_>) = alerts
+        .into_iter()
+        .partition(|a| !a.status.silenced_by.is_empty() || !a.status.inhibited_by.is_empty());
+    let mut preview = preview_alerts(&firing);
+    preview["already_silenced_or_inhibited"] = preview_alerts(&muted);
+    Ok(preview)
+}
+
+fn preview_alerts(alerts: &[GettableAlert]) -> serde_json::Value {
+    let listed: Vec<_> = alerts
+        .iter()
+        .take(SILENCE_PREVIEW_ALERT_LIMIT)
+        .map(|a| {
+            json!({
+                "fingerprint": a.fingerprint,
+                "labels": a.labels,
+                "state": a.status.state,
+                "starts_at": a.starts_at,
+                "silenced_by": a.status.silenced_by,
+                "inhibited_by": a.status.inhibited_by,
+            })
+        })
+        .collect();
+    json!({
+        "count": alerts.len(),
+        "truncated": alerts.len() > SILENCE_PREVIEW_ALERT_LIMIT,
+        "alerts": listed,
+    })
+}
+
+/// Token a dry run returns and the applying call must echo, so a silence change is only
+/// applied after that exact change was previewed
+fn confirmation_token(parts: &[&str]) -> String {
+    let mut hasher = DefaultHasher::new();
+    parts.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// Error result for an applying call without the token of a matching dry run
+fn unconfirmed_silence_change(confirm: Option<&str>, token: &str) -> Option<CallToolResult> {
+    if confirm == Some(token) {
+        return None;
+    }
+    Some(error_result(
+        "Not applied: preview the change with dry_run: true first and pass the confirm token it returns"
+            .to_string(),
+    ))
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn alertmanager_not_configured() -> CallToolResult {
+    error_result(
+        "Alertmanager is not configured; set ALERTMANAGER_URL or --alertmanager-url".to_string(),
+    )
+}
+
+/// Error result for admin tools invoked while they are disabled
+fn admin_tools_disabled() -> Option<CallToolResult> {
+    if admin_tools_enabled() {
+        return None;
+    }
+    Some(CallToolResult {
+        content: vec![CallToolResultContent::Text {
+            text: "Admin tools are disabled; start with --enable-admin-tools to use them"
+                .to_string(),
+        }],
+        is_error: true,
+    })
+}
+
+/// Reject backend-specific query options the configured backend does not understand
+fn check_flavor_options(options: &QueryOptions) -> Result<(), String> {
+    let set = options.thanos_options_set();
+    if !set.is_empty() && backend_flavor() != BackendFlavor::Thanos {
+        return Err(format!(
+            "{} only apply to Thanos; set PROMETHEUS_FLAVOR=thanos or --flavor thanos",
+            set.join(", ")
+        ));
+    }
+    if let Some(engine) = options.engine.as_deref() {
+        if engine != "prometheus" && engine != "thanos" {
+            return Err(format!(
+                "Invalid engine '{}' (expected prometheus or thanos)",
+                engine
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn silence_tools_disabled() -> Option<CallToolResult> {
+    if silence_tools_enabled() {
+        return None;
+    }
+    Some(error_result(
+        "Silence tools are disabled; start with --enable-silence-tools to use them".to_string(),
+    ))
+}
+
+fn error_result(text: String) -> CallToolResult {
+    CallToolResult {
+        content: vec![CallToolResultContent::Text { text }],
+        is_error: true,
+    }
+}
+
+fn json_result<T: Serialize + ?Sized>(value: &T) -> CallToolResult {
+    let text = serde_json::to_string_pretty(value).unwrap_or_else(|_| "{}".to_string());
+    CallToolResult {
+        content: vec![CallToolResultContent::Text { text }],
+        is_error: false,
+    }
+}
+
+/// Filter alerts by state, alertname and label matchers
+fn filter_alerts(
+    alerts: Vec<Alert>,
+    state: Option<&str>,
+    alertname: Option<&str>,
+    matchers: &[LabelMatcher],
+) -> Vec<Alert> {
+    alerts
+        .into_iter()
+        .filter(|a| state.is_none_or(|s| a.state.eq_ignore_ascii_case(s)))
+        .filter(|a| {
+            alertname.is_none_or(|n| a.labels.get("alertname").map(String::as_str) == Some(n))
+        })
+        .filter(|a| matches_all(matchers, &a.labels))
+        .collect()
+}
+
+/// Borrow an optional list of strings as `Vec<&str>` (empty when absent)
+fn as_str_vec(v: &Option<Vec<String>>) -> Vec<&str> {
+    v.iter().flatten().map(|s| s.as_str()).collect()
+}
+
+/// Warnings and info annotations of a query result as a separate content item,
+/// so they reach the model even when it only skims the JSON
+fn annotations_content(result: &PrometheusQueryResult) -> Option<CallToolResultContent> {
+    let mut lines = Vec::new();
+    if !result.warnings.is_empty() {
+        lines.push("Warnings:".to_string());
+        lines.extend(result.warnings.iter().map(|w| format!("- {}", w)));
+    }
+    if !result.infos.is_empty() {
+        lines.push("Info:".to_string());
+        lines.extend(result.infos.iter().map(|i| format!("- {}", i)));
+    }
+    if lines.is_empty() {
+        return None;
+    }
+    Some(CallToolResultContent::Text {
+        text: lines.join("\n"),
+    })
+}
+
+/// One-line summary of query statistics, when they were requested
+fn stats_content(result: &PrometheusQueryResult) -> Option<CallToolResultContent> {
+    let stats = result.data.stats.as_ref()?;
+    Some(CallToolResultContent::Text {
+        text: format!(
+            "Stats: {} samples processed, {} peak samples, {:.3}s total ({:.3}s eval, {:.3}s queued)",
+            stats.samples.total_queryable_samples,
+            stats.samples.peak_samples,
+            stats.timings.exec_total_time,
+            stats.timings.eval_total_time,
+            stats.timings.exec_queue_time
+        ),
+    })
+}
+
+fn err_string(err: &dyn std::fmt::Display) -> String {
+    err.to_string()
 }
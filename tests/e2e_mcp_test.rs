@@ -30,7 +30,7 @@ async fn test_mcp_server_tools_schema() {
         }
     });
 
-    writeln!(stdin, "{}", init_req.to_string()).expect("Failed to write to stdin");
+    writeln!(stdin, "{}", init_req).expect("Failed to write to stdin");
 
     // Send tools/list request
     let tools_req = json!({
@@ -40,7 +40,7 @@ async fn test_mcp_server_tools_schema() {
         "params": {}
     });
 
-    writeln!(stdin, "{}", tools_req.to_string()).expect("Failed to write to stdin");
+    writeln!(stdin, "{}", tools_req).expect("Failed to write to stdin");
 
     // Read response line by line
     use std::io::{BufRead, BufReader};
@@ -127,4 +127,5 @@ async fn test_mcp_server_tools_schema() {
 
     // Clean up
     child.kill().expect("Failed to kill MCP server process");
+    let _ = child.wait();
 }
@@ -1,21 +1,50 @@
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use async_trait::async_trait;
 
+use prometheus_mcp::mcp::alertmanager_client::{
+    set_alertmanager, AlertFilter, AlertGroup, AlertStatus, AlertmanagerConfigStatus,
+    AlertmanagerRepository, AlertmanagerStatus, ClusterStatus, GettableAlert, PeerStatus, Receiver,
+    Silence, SilenceMatcher, SilenceStatus,
+};
+use prometheus_mcp::mcp::exposition::{parse_text_format, MetricFamily};
+use prometheus_mcp::mcp::prometheus_config::BackendFlavor;
 use prometheus_mcp::mcp::repository::PrometheusRepository;
+use prometheus_mcp::mcp::scrape::set_scrape_allowed_hosts;
+use prometheus_mcp::mcp::tenant::set_allowed_tenants;
 use prometheus_mcp::mcp::tools::{
-    prometheus_get_label_values, prometheus_get_metadata, prometheus_get_series,
-    prometheus_list_metrics, prometheus_query, prometheus_query_range, tools_list,
-    PrometheusGetLabelValuesRequest, PrometheusGetMetadataRequest, PrometheusGetSeriesRequest,
-    PrometheusListMetricsRequest, PrometheusQueryRangeRequest, PrometheusQueryRequest,
+    alertmanager_create_silence, alertmanager_expire_silence, alertmanager_extend_silence,
+    alertmanager_get_status, alertmanager_list_alert_groups, alertmanager_list_alerts,
+    alertmanager_list_silences, prometheus_admin_clean_tombstones, prometheus_admin_delete_series,
+    prometheus_admin_snapshot, prometheus_federate, prometheus_format_query, prometheus_get_alerts,
+    prometheus_get_label_values, prometheus_get_metadata, prometheus_get_rules,
+    prometheus_get_series, prometheus_get_status, prometheus_get_targets_metadata,
+    prometheus_list_labels, prometheus_list_metrics, prometheus_parse_query, prometheus_query,
+    prometheus_query_exemplars, prometheus_query_range, prometheus_read_raw,
+    prometheus_scrape_target, prometheus_tsdb_status, set_admin_tools_enabled, set_backend_flavor,
+    set_silence_tools_enabled, tools_list, AlertmanagerAlertFilterRequest,
+    AlertmanagerCreateSilenceRequest, AlertmanagerExpireSilenceRequest,
+    AlertmanagerExtendSilenceRequest, AlertmanagerGetStatusRequest,
+    AlertmanagerListSilencesRequest, PrometheusAdminCleanTombstonesRequest,
+    PrometheusAdminDeleteSeriesRequest, PrometheusAdminSnapshotRequest, PrometheusFederateRequest,
+    PrometheusFormatQueryRequest, PrometheusGetAlertsRequest, PrometheusGetLabelValuesRequest,
+    PrometheusGetMetadataRequest, PrometheusGetRulesRequest, PrometheusGetSeriesRequest,
+    PrometheusGetStatusRequest, PrometheusGetTargetsMetadataRequest, PrometheusListLabelsRequest,
+    PrometheusListMetricsRequest, PrometheusParseQueryRequest, PrometheusQueryExemplarsRequest,
+    PrometheusQueryRangeRequest, PrometheusQueryRequest, PrometheusReadRawRequest,
+    PrometheusScrapeTargetRequest, PrometheusTsdbStatusRequest,
 };
 use prometheus_mcp::mcp::types::{CallToolResult, CallToolResultContent};
 use prometheus_mcp::set_repository;
 
 use prometheus_mcp::mcp::prometheus_client::{
-    MetricMetadata, PrometheusData, PrometheusQueryResult,
+    Alert, AlertingRule, Exemplar, ExemplarSeries, HeadStats, HistogramBucket, MetricMetadata,
+    NativeHistogram, PrometheusData, PrometheusError, PrometheusQueryResult, PrometheusResult,
+    PrometheusResultData, QueryOptions, QuerySamples, QueryStats, QueryTimings, RawSeries, Rule,
+    RuleGroup, SeriesResult, StatusSection, TargetMetadata, TsdbStat, TsdbStatus,
 };
+use serde_json::{json, Value};
 
 struct MockRepo;
 
@@ -23,16 +52,44 @@ struct MockRepo;
 impl PrometheusRepository for MockRepo {
     async fn query(
         &self,
-        _query: &str,
+        query: &str,
         _time: Option<&str>,
+        options: &QueryOptions,
     ) -> Result<PrometheusQueryResult, prometheus_mcp::mcp::prometheus_client::PrometheusError>
     {
+        let result = match query {
+            "time()" => PrometheusResultData::Scalar((1_700_000_000.0, "1700000000".into())),
+            "sum(" => {
+                return Err(PrometheusError::from_api_error(
+                    "bad_data",
+                    "1:5: parse error: unclosed left parenthesis",
+                ))
+            }
+            _ => PrometheusResultData::Vector(vec![]),
+        };
         Ok(PrometheusQueryResult {
             status: "success".into(),
             data: PrometheusData {
-                result_type: "vector".into(),
-                result: vec![],
+                result,
+                stats: options.stats.then(|| QueryStats {
+                    timings: QueryTimings {
+                        eval_total_time: 0.012,
+                        exec_total_time: 0.0125,
+                        ..Default::default()
+                    },
+                    samples: QuerySamples {
+                        total_queryable_samples: 1500,
+                        peak_samples: 120,
+                    },
+                }),
+            },
+            // Thanos reports failed stores as warnings under partial_response
+            warnings: if options.partial_response == Some(true) {
+                vec!["receive-0: rpc error: code = Unavailable".into()]
+            } else {
+                vec![]
             },
+            infos: vec![],
         })
     }
 
@@ -42,14 +99,40 @@ impl PrometheusRepository for MockRepo {
         _start: &str,
         _end: &str,
         _step: &str,
+        _options: &QueryOptions,
     ) -> Result<PrometheusQueryResult, prometheus_mcp::mcp::prometheus_client::PrometheusError>
     {
         Ok(PrometheusQueryResult {
             status: "success".into(),
             data: PrometheusData {
-                result_type: "matrix".into(),
-                result: vec![],
+                result: PrometheusResultData::Matrix(vec![PrometheusResult {
+                    metric: HashMap::from([(
+                        "__name__".to_string(),
+                        "http_request_duration_seconds".to_string(),
+                    )]),
+                    value: None,
+                    values: None,
+                    histogram: None,
+                    histograms: Some(vec![(
+                        0.0,
+                        NativeHistogram {
+                            count: "3".into(),
+                            sum: "0.7".into(),
+                            buckets: vec![HistogramBucket::from((
+                                0,
+                                "0.125".to_string(),
+                                "0.25".to_string(),
+                                "3".to_string(),
+                            ))],
+                        },
+                    )]),
+                }]),
+                stats: None,
             },
+            warnings: vec![],
+            infos: vec![
+                "PromQL info: metric might not be a counter, name does not end in _total/_sum/_count/_bucket: \"http_request_duration_seconds\"".into(),
+            ],
         })
     }
 
@@ -61,10 +144,43 @@ impl PrometheusRepository for MockRepo {
 
     async fn get_metadata(
         &self,
-        metric: &str,
+        metric: Option<&str>,
+        limit: Option<u64>,
+        _limit_per_metric: Option<u64>,
     ) -> Result<Vec<MetricMetadata>, prometheus_mcp::mcp::prometheus_client::PrometheusError> {
-        Ok(vec![MetricMetadata {
-            metric: metric.into(),
+        let all = vec![
+            MetricMetadata {
+                metric: "up".into(),
+                type_name: "gauge".into(),
+                help: "Target is up".into(),
+                unit: String::new(),
+            },
+            MetricMetadata {
+                metric: "node_cpu_seconds_total".into(),
+                type_name: "counter".into(),
+                help: "help".into(),
+                unit: "seconds".into(),
+            },
+        ];
+        Ok(all
+            .into_iter()
+            .filter(|m| metric.is_none_or(|name| m.metric == name))
+            .take(limit.map_or(usize::MAX, |l| l as usize))
+            .collect())
+    }
+
+    async fn get_targets_metadata(
+        &self,
+        _match_target: Option<&str>,
+        metric: Option<&str>,
+        _limit: Option<u64>,
+    ) -> Result<Vec<TargetMetadata>, prometheus_mcp::mcp::prometheus_client::PrometheusError> {
+        Ok(vec![TargetMetadata {
+            target: HashMap::from([
+                ("job".to_string(), "node".to_string()),
+                ("instance".to_string(), "localhost:9100".to_string()),
+            ]),
+            metric: metric.unwrap_or("node_cpu_seconds_total").into(),
             type_name: "counter".into(),
             help: "help".into(),
             unit: "seconds".into(),
@@ -74,22 +190,335 @@ impl PrometheusRepository for MockRepo {
     async fn get_series(
         &self,
         _match_strings: Vec<&str>,
-    ) -> Result<Vec<HashMap<String, String>>, prometheus_mcp::mcp::prometheus_client::PrometheusError>
-    {
-        let mut m = HashMap::new();
-        m.insert("__name__".into(), "up".into());
-        Ok(vec![m])
+        _start: Option<&str>,
+        _end: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<SeriesResult, prometheus_mcp::mcp::prometheus_client::PrometheusError> {
+        let mut series: Vec<HashMap<String, String>> = (0..3)
+            .map(|i| {
+                HashMap::from([
+                    ("__name__".to_string(), "up".to_string()),
+                    ("instance".to_string(), format!("host-{}", i)),
+                ])
+            })
+            .collect();
+        let truncated = limit.is_some_and(|l| (l as usize) < series.len());
+        series.truncate(limit.unwrap_or(u64::MAX) as usize);
+        Ok(SeriesResult { series, truncated })
     }
 
     async fn get_label_values(
         &self,
         label_name: &str,
+        match_strings: Vec<&str>,
+        _start: Option<&str>,
+        _end: Option<&str>,
+        limit: Option<u64>,
     ) -> Result<Vec<String>, prometheus_mcp::mcp::prometheus_client::PrometheusError> {
+        let mut values = vec![format!("{}-a", label_name), format!("{}-b", label_name)];
+        if !match_strings.is_empty() {
+            values.retain(|v| v.ends_with("-a"));
+        }
+        values.truncate(limit.unwrap_or(u64::MAX) as usize);
+        Ok(values)
+    }
+
+    async fn list_labels(
+        &self,
+        _match_strings: Vec<&str>,
+        _start: Option<&str>,
+        _end: Option<&str>,
+        limit: Option<u64>,
+    ) -> Result<Vec<String>, prometheus_mcp::mcp::prometheus_client::PrometheusError> {
+        let mut labels = vec!["__name__".to_string(), "instance".into(), "job".into()];
+        labels.truncate(limit.unwrap_or(u64::MAX) as usize);
+        Ok(labels)
+    }
+
+    async fn get_alerts(
+        &self,
+    ) -> Result<Vec<Alert>, prometheus_mcp::mcp::prometheus_client::PrometheusError> {
+        let alert = |name: &str, state: &str, severity: &str| Alert {
+            labels: HashMap::from([
+                ("alertname".to_string(), name.to_string()),
+                ("severity".to_string(), severity.to_string()),
+            ]),
+            annotations: HashMap::from([("summary".to_string(), format!("{} alert", name))]),
+            state: state.into(),
+            active_at: Some("2025-09-27T12:00:00Z".into()),
+            value: Some("1e+00".into()),
+        };
+        Ok(vec![
+            alert("HighLatency", "firing", "critical"),
+            alert("DiskFilling", "pending", "warning"),
+        ])
+    }
+
+    async fn get_rules(
+        &self,
+        _rule_type: Option<&str>,
+        rule_names: Vec<&str>,
+        _rule_groups: Vec<&str>,
+        _files: Vec<&str>,
+    ) -> Result<Vec<RuleGroup>, prometheus_mcp::mcp::prometheus_client::PrometheusError> {
+        let name = rule_names.first().copied().unwrap_or("HighLatency");
+        Ok(vec![RuleGroup {
+            name: "latency".into(),
+            file: "/etc/prometheus/rules.yaml".into(),
+            rules: vec![Rule::Alerting(AlertingRule {
+                name: name.into(),
+                query: "histogram_quantile(0.99, rate(http_duration_seconds_bucket[5m])) > 0.5"
+                    .into(),
+                duration: 300.0,
+                keep_firing_for: 0.0,
+                labels: HashMap::new(),
+                annotations: HashMap::new(),
+                alerts: vec![],
+                state: Some("inactive".into()),
+                health: "ok".into(),
+                last_error: None,
+                evaluation_time: 0.001,
+                last_evaluation: None,
+            })],
+            interval: 30.0,
+            evaluation_time: 0.001,
+            last_evaluation: None,
+        }])
+    }
+
+    async fn get_status(
+        &self,
+        section: StatusSection,
+    ) -> Result<Value, prometheus_mcp::mcp::prometheus_client::PrometheusError> {
+        Ok(match section {
+            StatusSection::BuildInfo => json!({"version": "3.5.0", "goVersion": "go1.24"}),
+            StatusSection::RuntimeInfo => json!({"storageRetention": "15d"}),
+            StatusSection::Flags => json!({"storage.tsdb.retention.time": "15d"}),
+            StatusSection::Config => json!({
+                "yaml": "global:\n  scrape_interval: 15s\nscrape_configs:\n  - job_name: api\n    basic_auth:\n      password: hunter2\n"
+            }),
+        })
+    }
+
+    async fn get_tsdb_status(
+        &self,
+        limit: Option<u64>,
+    ) -> Result<TsdbStatus, prometheus_mcp::mcp::prometheus_client::PrometheusError> {
+        let top = (0..limit.unwrap_or(10))
+            .map(|i| TsdbStat {
+                name: format!("metric_{}", i),
+                value: 1000 - i,
+            })
+            .collect();
+        Ok(TsdbStatus {
+            head_stats: Some(HeadStats {
+                num_series: 12345,
+                num_label_pairs: 678,
+                chunk_count: 9000,
+                min_time: 1_700_000_000_000,
+                max_time: 1_700_007_200_000,
+            }),
+            series_count_by_metric_name: top,
+            label_value_count_by_label_name: vec![],
+            memory_in_bytes_by_label_name: vec![],
+            series_count_by_label_value_pair: vec![],
+        })
+    }
+
+    async fn query_exemplars(
+        &self,
+        _query: &str,
+        _start: Option<&str>,
+        _end: Option<&str>,
+    ) -> Result<Vec<ExemplarSeries>, prometheus_mcp::mcp::prometheus_client::PrometheusError> {
+        Ok(vec![ExemplarSeries {
+            series_labels: HashMap::from([(
+                "__name__".to_string(),
+                "http_duration_seconds_bucket".to_string(),
+            )]),
+            exemplars: vec![Exemplar {
+                labels: HashMap::from([("trace_id".to_string(), "4bf92f3577b34da6".to_string())]),
+                value: "1.27".into(),
+                timestamp: 1_700_000_000.123,
+                trace_id: Some("4bf92f3577b34da6".into()),
+            }],
+        }])
+    }
+
+    async fn format_query(
+        &self,
+        query: &str,
+    ) -> Result<String, prometheus_mcp::mcp::prometheus_client::PrometheusError> {
+        Ok(query.split_whitespace().collect::<Vec<_>>().join(" "))
+    }
+
+    async fn parse_query(
+        &self,
+        _query: &str,
+    ) -> Result<Value, prometheus_mcp::mcp::prometheus_client::PrometheusError> {
+        Err(
+            prometheus_mcp::mcp::prometheus_client::PrometheusError::Unsupported(
+                "/api/v1/parse_query is not available on this backend (404 Not Found)".into(),
+            ),
+        )
+    }
+
+    async fn snapshot(
+        &self,
+        _skip_head: bool,
+    ) -> Result<String, prometheus_mcp::mcp::prometheus_client::PrometheusError> {
+        Ok("20250927T120000Z-6e6f6e6f".into())
+    }
+
+    async fn delete_series(
+        &self,
+        _match_strings: Vec<&str>,
+        _start: Option<&str>,
+        _end: Option<&str>,
+    ) -> Result<(), prometheus_mcp::mcp::prometheus_client::PrometheusError> {
+        Ok(())
+    }
+
+    async fn clean_tombstones(
+        &self,
+    ) -> Result<(), prometheus_mcp::mcp::prometheus_client::PrometheusError> {
+        Ok(())
+    }
+
+    async fn read_raw(
+        &self,
+        _selector: &str,
+        start_ms: i64,
+        end_ms: i64,
+    ) -> Result<Vec<RawSeries>, prometheus_mcp::mcp::prometheus_client::PrometheusError> {
+        Ok(vec![RawSeries {
+            labels: HashMap::from([("__name__".to_string(), "up".to_string())]),
+            samples: vec![(start_ms, "1".to_string()), (end_ms, "NaN".to_string())],
+        }])
+    }
+
+    async fn federate(
+        &self,
+        match_strings: Vec<&str>,
+    ) -> Result<Vec<MetricFamily>, prometheus_mcp::mcp::prometheus_client::PrometheusError> {
+        assert_eq!(match_strings, vec!["{job=\"edge\"}"]);
+        let text = "# TYPE up gauge\nup{instance=\"edge-1:9090\",job=\"edge\"} 1 1700000000000\n";
+        Ok(parse_text_format(text).unwrap())
+    }
+}
+
+/// Records alert filters and posted or expired silences for assertions
+#[derive(Default)]
+struct MockAlertmanager {
+    filters: Mutex<Vec<AlertFilter>>,
+    posted: Mutex<Vec<Silence>>,
+    expired: Mutex<Vec<String>>,
+}
+
+fn mock_silence(id: &str, state: &str) -> Silence {
+    Silence {
+        id: Some(id.into()),
+        status: Some(SilenceStatus {
+            state: state.into(),
+        }),
+        matchers: vec![SilenceMatcher {
+            name: "alertname".into(),
+            value: "HighLatency".into(),
+            is_regex: false,
+            is_equal: true,
+        }],
+        starts_at: "2025-09-27T12:00:00Z".into(),
+        ends_at: "2999-01-01T00:00:00Z".into(),
+        created_by: "oncall".into(),
+        comment: "deploy".into(),
+        ..Default::default()
+    }
+}
+
+#[async_trait]
+impl AlertmanagerRepository for MockAlertmanager {
+    async fn get_alerts(
+        &self,
+        filter: &AlertFilter,
+    ) -> Result<Vec<GettableAlert>, PrometheusError> {
+        self.filters.lock().unwrap().push(filter.clone());
+        let alert = |fingerprint: &str, state: &str, silenced_by: Vec<String>| GettableAlert {
+            labels: HashMap::from([("alertname".to_string(), "HighLatency".to_string())]),
+            receivers: vec![Receiver {
+                name: "pagerduty".into(),
+            }],
+            fingerprint: fingerprint.into(),
+            status: AlertStatus {
+                state: state.into(),
+                silenced_by,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut alerts = vec![alert("a1b2c3", "active", vec![])];
+        if !filter.filter.is_empty() {
+            alerts.push(alert("d4e5f6", "suppressed", vec!["s-active".into()]));
+        }
+        Ok(alerts)
+    }
+
+    async fn get_alert_groups(
+        &self,
+        _filter: &AlertFilter,
+    ) -> Result<Vec<AlertGroup>, PrometheusError> {
+        Ok(vec![AlertGroup {
+            labels: HashMap::from([("alertname".to_string(), "HighLatency".to_string())]),
+            receiver: Receiver {
+                name: "pagerduty".into(),
+            },
+            alerts: vec![],
+        }])
+    }
+
+    async fn get_status(&self) -> Result<AlertmanagerStatus, PrometheusError> {
+        Ok(AlertmanagerStatus {
+            cluster: ClusterStatus {
+                name: None,
+                status: "ready".into(),
+                peers: vec![PeerStatus {
+                    name: "am-0".into(),
+                    address: "10.0.0.1:9094".into(),
+                }],
+            },
+            config: AlertmanagerConfigStatus {
+                original: "receivers:\n  - name: pd\n    pagerduty_configs:\n      - routing_key: abc123\n        auth_token: s3cret\n".into(),
+            },
+            ..Default::default()
+        })
+    }
+
+    async fn get_silences(&self, _filter: &[String]) -> Result<Vec<Silence>, PrometheusError> {
         Ok(vec![
-            format!("{}-a", label_name),
-            format!("{}-b", label_name),
+            mock_silence("s-active", "active"),
+            mock_silence("s-old", "expired"),
         ])
     }
+
+    async fn get_silence(&self, id: &str) -> Result<Silence, PrometheusError> {
+        match id {
+            "s-active" => Ok(mock_silence(id, "active")),
+            "s-old" => Ok(mock_silence(id, "expired")),
+            _ => Err(PrometheusError::NotFound(format!(
+                "silence {} not found",
+                id
+            ))),
+        }
+    }
+
+    async fn post_silence(&self, silence: &Silence) -> Result<String, PrometheusError> {
+        self.posted.lock().unwrap().push(silence.clone());
+        Ok(silence.id.clone().unwrap_or_else(|| "s-new".into()))
+    }
+
+    async fn expire_silence(&self, id: &str) -> Result<(), PrometheusError> {
+        self.expired.lock().unwrap().push(id.to_string());
+        Ok(())
+    }
 }
 
 fn extract_text(result: &CallToolResult) -> String {
@@ -108,20 +537,106 @@ async fn test_tools_with_mock_repo() {
     let res = prometheus_query(PrometheusQueryRequest {
         query: "up".into(),
         time: None,
+        stats: None,
+        timeout: None,
+        limit: None,
+        dedup: None,
+        partial_response: None,
+        max_source_resolution: None,
+        engine: None,
     })
     .await
     .unwrap();
     assert!(!res.is_error);
 
+    let res = prometheus_query(PrometheusQueryRequest {
+        query: "up".into(),
+        time: None,
+        stats: Some(true),
+        timeout: Some("5s".into()),
+        limit: Some(10),
+        dedup: None,
+        partial_response: None,
+        max_source_resolution: None,
+        engine: None,
+    })
+    .await
+    .unwrap();
+    assert!(!res.is_error);
+    let v: Value = serde_json::from_str(&extract_text(&res)).unwrap();
+    assert_eq!(v["data"]["stats"]["samples"]["peakSamples"], 120);
+    match &res.content[1] {
+        CallToolResultContent::Text { text } => assert_eq!(
+            text,
+            "Stats: 1500 samples processed, 120 peak samples, 0.013s total (0.012s eval, 0.000s queued)"
+        ),
+        _ => panic!("expected stats text"),
+    }
+
+    let res = prometheus_query(PrometheusQueryRequest {
+        query: "time()".into(),
+        time: None,
+        stats: None,
+        timeout: None,
+        limit: None,
+        dedup: None,
+        partial_response: None,
+        max_source_resolution: None,
+        engine: None,
+    })
+    .await
+    .unwrap();
+    assert!(!res.is_error);
+    let v: Value = serde_json::from_str(&extract_text(&res)).unwrap();
+    assert_eq!(v["data"]["resultType"], "scalar");
+    assert_eq!(v["data"]["result"][1], "1700000000");
+
+    let res = prometheus_query(PrometheusQueryRequest {
+        query: "sum(".into(),
+        time: None,
+        stats: None,
+        timeout: None,
+        limit: None,
+        dedup: None,
+        partial_response: None,
+        max_source_resolution: None,
+        engine: None,
+    })
+    .await
+    .unwrap();
+    assert!(res.is_error);
+    assert_eq!(
+        extract_text(&res),
+        "Bad data: 1:5: parse error: unclosed left parenthesis"
+    );
+
     let res = prometheus_query_range(PrometheusQueryRangeRequest {
         query: "up".into(),
         start: "0".into(),
         end: "1".into(),
         step: "1".into(),
+        stats: None,
+        timeout: None,
+        limit: None,
+        dedup: None,
+        partial_response: None,
+        max_source_resolution: None,
+        engine: None,
     })
     .await
     .unwrap();
     assert!(!res.is_error);
+    let v: Value = serde_json::from_str(&extract_text(&res)).unwrap();
+    let bucket = &v["data"]["result"][0]["histograms"][0][1]["buckets"][0];
+    assert_eq!(bucket["lower"], "0.125");
+    assert_eq!(bucket["upper_inclusive"], true);
+    assert_eq!(res.content.len(), 2);
+    match &res.content[1] {
+        CallToolResultContent::Text { text } => {
+            assert!(text.starts_with("Info:\n- PromQL info: metric might not be a counter"))
+        }
+        _ => panic!("expected text annotations"),
+    }
 
     let res = prometheus_list_metrics(PrometheusListMetricsRequest {})
         .await
@@ -129,26 +644,592 @@ async fn test_tools_with_mock_repo() {
     assert!(extract_text(&res).contains("up"));
 
     let res = prometheus_get_metadata(PrometheusGetMetadataRequest {
-        metric: "up".into(),
+        metric: Some("node_cpu_seconds_total".into()),
+        limit: None,
+        limit_per_metric: None,
     })
     .await
     .unwrap();
     assert!(extract_text(&res).contains("counter"));
 
+    let res = prometheus_get_metadata(PrometheusGetMetadataRequest {
+        metric: None,
+        limit: None,
+        limit_per_metric: None,
+    })
+    .await
+    .unwrap();
+    let v: Value = serde_json::from_str(&extract_text(&res)).unwrap();
+    assert_eq!(v.as_array().unwrap().len(), 2);
+    assert_eq!(v[0]["unit"], "");
+
+    let res = prometheus_get_metadata(PrometheusGetMetadataRequest {
+        metric: None,
+        limit: Some(1),
+        limit_per_metric: Some(1),
+    })
+    .await
+    .unwrap();
+    let v: Value = serde_json::from_str(&extract_text(&res)).unwrap();
+    assert_eq!(v.as_array().unwrap().len(), 1);
+
+    let res = prometheus_get_targets_metadata(PrometheusGetTargetsMetadataRequest {
+        match_target: Some("{job=\"node\"}".into()),
+        metric: Some("node_cpu_seconds_total".into()),
+        limit: None,
+    })
+    .await
+    .unwrap();
+    assert!(!res.is_error);
+    let v: Value = serde_json::from_str(&extract_text(&res)).unwrap();
+    assert_eq!(v[0]["target"]["job"], "node");
+    assert_eq!(v[0]["type"], "counter");
+
     let res = prometheus_get_series(PrometheusGetSeriesRequest {
         match_strings: vec!["up".into()],
+        start: None,
+        end: None,
+        limit: None,
     })
     .await
     .unwrap();
     assert!(extract_text(&res).contains("__name__"));
+    assert_eq!(res.content.len(), 1);
+
+    let res = prometheus_get_series(PrometheusGetSeriesRequest {
+        match_strings: vec!["up".into()],
+        start: Some("2025-09-27T12:00:00Z".into()),
+        end: Some("2025-09-27T13:00:00Z".into()),
+        limit: Some(2),
+    })
+    .await
+    .unwrap();
+    assert!(!extract_text(&res).contains("host-2"));
+    assert!(res.content.iter().any(|c| matches!(
+        c,
+        CallToolResultContent::Text { text } if text.contains("truncated to 2 series")
+    )));
 
     let res = prometheus_get_label_values(PrometheusGetLabelValuesRequest {
         label_name: "job".into(),
+        match_strings: None,
+        start: None,
+        end: None,
+        limit: None,
     })
     .await
     .unwrap();
     assert!(extract_text(&res).contains("job-a"));
 
+    let res = prometheus_get_label_values(PrometheusGetLabelValuesRequest {
+        label_name: "namespace".into(),
+        match_strings: Some(vec!["up{job=\"api\"}".into()]),
+        start: Some("2025-09-27T12:00:00Z".into()),
+        end: Some("2025-09-27T13:00:00Z".into()),
+        limit: Some(10),
+    })
+    .await
+    .unwrap();
+    let text = extract_text(&res);
+    assert!(text.contains("namespace-a"));
+    assert!(!text.contains("namespace-b"));
+
+    let res = prometheus_list_labels(PrometheusListLabelsRequest {
+        match_strings: None,
+        start: None,
+        end: None,
+        limit: Some(2),
+    })
+    .await
+    .unwrap();
+    let text = extract_text(&res);
+    assert!(text.contains("instance"));
+    assert!(!text.contains("job"));
+
+    let res = prometheus_get_alerts(PrometheusGetAlertsRequest {
+        state: Some("firing".into()),
+        alertname: None,
+        matchers: Some(vec!["severity=~\"crit.*\"".into()]),
+    })
+    .await
+    .unwrap();
+    let text = extract_text(&res);
+    assert!(!res.is_error);
+    assert!(text.contains("HighLatency"));
+    assert!(text.contains("activeAt"));
+    assert!(!text.contains("DiskFilling"));
+
+    let res = prometheus_get_alerts(PrometheusGetAlertsRequest {
+        state: None,
+        alertname: None,
+        matchers: Some(vec!["severity".into()]),
+    })
+    .await
+    .unwrap();
+    assert!(res.is_error);
+
+    let res = prometheus_tsdb_status(PrometheusTsdbStatusRequest { limit: Some(2) })
+        .await
+        .unwrap();
+    let text = extract_text(&res);
+    assert!(text.contains("\"numSeries\": 12345"));
+    assert!(text.contains("metric_1"));
+    assert!(!text.contains("metric_2"));
+
+    let res = prometheus_query_exemplars(PrometheusQueryExemplarsRequest {
+        query: "http_duration_seconds_bucket".into(),
+        start: Some("0".into()),
+        end: Some("1".into()),
+    })
+    .await
+    .unwrap();
+    let text = extract_text(&res);
+    assert!(text.contains("\"trace_id\": \"4bf92f3577b34da6\""));
+    assert!(text.contains("seriesLabels"));
+
+    let res = prometheus_format_query(PrometheusFormatQueryRequest {
+        query: "sum(rate(up[5m]))   by (job)".into(),
+    })
+    .await
+    .unwrap();
+    assert!(!res.is_error);
+    assert_eq!(extract_text(&res), "sum(rate(up[5m])) by (job)");
+
+    let res = prometheus_parse_query(PrometheusParseQueryRequest { query: "up".into() })
+        .await
+        .unwrap();
+    assert!(res.is_error);
+    assert!(extract_text(&res).contains("not available"));
+
+    // Admin tools are off by default and hidden from tools/list
+    let res = prometheus_admin_clean_tombstones(PrometheusAdminCleanTombstonesRequest {
+        dry_run: Some(false),
+    })
+    .await
+    .unwrap();
+    assert!(res.is_error);
+    assert!(extract_text(&res).contains("--enable-admin-tools"));
+    let tools = tools_list(None).await.unwrap().tools;
+    assert!(!tools
+        .iter()
+        .any(|t| t.name.starts_with("prometheus_admin_")));
+
+    // Alertmanager tools are hidden until an Alertmanager is configured
+    let tools = tools_list(None).await.unwrap().tools;
+    assert!(!tools.iter().any(|t| t.name.starts_with("alertmanager_")));
+    let res = alertmanager_get_status(AlertmanagerGetStatusRequest {})
+        .await
+        .unwrap();
+    assert!(res.is_error);
+    assert!(extract_text(&res).contains("ALERTMANAGER_URL"));
+
+    let am = Arc::new(MockAlertmanager::default());
+    set_alertmanager(am.clone());
+    let tools = tools_list(None).await.unwrap().tools;
+    assert_eq!(
+        tools
+            .iter()
+            .filter(|t| t.name.starts_with("alertmanager_"))
+            .count(),
+        4
+    );
+
+    let res = alertmanager_list_alerts(AlertmanagerAlertFilterRequest {
+        filter: Some(vec!["severity=\"page\"".into()]),
+        receiver: None,
+        active: None,
+        silenced: None,
+        inhibited: Some(false),
+    })
+    .await
+    .unwrap();
+    assert!(!res.is_error);
+    let filter = am.filters.lock().unwrap().pop().unwrap();
+    assert_eq!(filter.filter, vec!["severity=\"page\""]);
+    assert_eq!(filter.inhibited, Some(false));
+    let v: Value = serde_json::from_str(&extract_text(&res)).unwrap();
+    assert_eq!(v[0]["receivers"][0]["name"], "pagerduty");
+    assert_eq!(v[0]["status"]["state"], "active");
+    assert_eq!(v[0]["status"]["inhibitedBy"], json!([]));
+
+    let res = alertmanager_list_alert_groups(AlertmanagerAlertFilterRequest {
+        filter: None,
+        receiver: Some("pager.*".into()),
+        active: None,
+        silenced: None,
+        inhibited: None,
+    })
+    .await
+    .unwrap();
+    let v: Value = serde_json::from_str(&extract_text(&res)).unwrap();
+    assert_eq!(v[0]["receiver"]["name"], "pagerduty");
+
+    let res = alertmanager_get_status(AlertmanagerGetStatusRequest {})
+        .await
+        .unwrap();
+    let v: Value = serde_json::from_str(&extract_text(&res)).unwrap();
+    assert_eq!(v["cluster"]["peers"][0]["address"], "10.0.0.1:9094");
+    let original = v["config"]["original"].as_str().unwrap();
+    assert!(!original.contains("s3cret"));
+
+    let res = alertmanager_list_silences(AlertmanagerListSilencesRequest {
+        filter: None,
+        state: Some("active".into()),
+    })
+    .await
+    .unwrap();
+    let v: Value = serde_json::from_str(&extract_text(&res)).unwrap();
+    assert_eq!(v.as_array().unwrap().len(), 1);
+    assert_eq!(v[0]["id"], "s-active");
+    assert_eq!(v[0]["matchers"][0]["isEqual"], true);
+
+    // Silence write tools are off by default and hidden from tools/list
+    let res = alertmanager_expire_silence(AlertmanagerExpireSilenceRequest {
+        id: "s-active".into(),
+        dry_run: Some(false),
+        confirm: None,
+    })
+    .await
+    .unwrap();
+    assert!(res.is_error);
+    assert!(extract_text(&res).contains("--enable-silence-tools"));
+    assert!(am.expired.lock().unwrap().is_empty());
+
+    set_silence_tools_enabled(true);
+    let tools = tools_list(None).await.unwrap().tools;
+    let create_tool = tools
+        .iter()
+        .find(|t| t.name == "alertmanager_create_silence")
+        .expect("create_silence listed when enabled");
+    let annotations = serde_json::to_value(create_tool.annotations.as_ref().unwrap()).unwrap();
+    assert_eq!(annotations["destructiveHint"], true);
+
+    // Creating previews the matching alerts and does nothing without dry_run: false
+    let res = alertmanager_create_silence(AlertmanagerCreateSilenceRequest {
+        matchers: vec!["alertname=\"HighLatency\", env!~\"dev|ci\"".into()],
+        duration: "2h".into(),
+        comment: "INC-42 database failover".into(),
+        created_by: None,
+        dry_run: None,
+        confirm: None,
+    })
+    .await
+    .unwrap();
+    assert!(!res.is_error);
+    let v: Value = serde_json::from_str(&extract_text(&res)).unwrap();
+    assert_eq!(v["dry_run"], true);
+    // Firing alerts are listed apart from those already muted
+    assert_eq!(v["matching_alerts"]["count"], 1);
+    assert_eq!(v["matching_alerts"]["alerts"][0]["fingerprint"], "a1b2c3");
+    let muted = &v["matching_alerts"]["already_silenced_or_inhibited"];
+    assert_eq!(muted["count"], 1);
+    assert_eq!(muted["alerts"][0]["fingerprint"], "d4e5f6");
+    let other_token = v["confirm"].as_str().unwrap().to_string();
+    assert_eq!(v["silence"]["createdBy"], "prometheus-mcp");
+    assert_eq!(v["silence"]["matchers"][1]["isRegex"], true);
+    let filter = am.filters.lock().unwrap().pop().unwrap();
+    assert_eq!(
+        filter.filter,
+        vec!["alertname=\"HighLatency\"", "env!~\"dev|ci\""]
+    );
+    assert!(am.posted.lock().unwrap().is_empty());
+
+    // Applying needs the token of a preview of the same change
+    let create = |dry_run: bool, confirm: Option<String>| {
+        alertmanager_create_silence(AlertmanagerCreateSilenceRequest {
+            matchers: vec!["alertname=\"HighLatency\"".into()],
+            duration: "2h".into(),
+            comment: "INC-42 database failover".into(),
+            created_by: Some("alice".into()),
+            dry_run: Some(dry_run),
+            confirm,
+        })
+    };
+    for confirm in [None, Some(other_token)] {
+        let res = create(false, confirm).await.unwrap();
+        assert!(res.is_error);
+        assert!(extract_text(&res).contains("confirm token"));
+    }
+    assert!(am.posted.lock().unwrap().is_empty());
+    let v: Value = serde_json::from_str(&extract_text(&create(true, None).await.unwrap())).unwrap();
+    let token = v["confirm"].as_str().unwrap().to_string();
+    let res = create(false, Some(token)).await.unwrap();
+    let v: Value = serde_json::from_str(&extract_text(&res)).unwrap();
+    assert_eq!(v["silence_id"], "s-new");
+    let posted = am.posted.lock().unwrap().pop().unwrap();
+    assert!(posted.id.is_none());
+    assert_eq!(posted.created_by, "alice");
+
+    // Matchers that match everything and unparsable durations are rejected
+    for (matcher, duration) in [("env!=\"prod\"", "1h"), ("alertname=\"X\"", "soon")] {
+        let res = alertmanager_create_silence(AlertmanagerCreateSilenceRequest {
+            matchers: vec![matcher.into()],
+            duration: duration.into(),
+            comment: "c".into(),
+            created_by: None,
+            dry_run: Some(false),
+            confirm: None,
+        })
+        .await
+        .unwrap();
+        assert!(res.is_error);
+    }
+    assert!(am.posted.lock().unwrap().is_empty());
+
+    let extend = |id: &str, dry_run: bool, confirm: Option<String>| {
+        alertmanager_extend_silence(AlertmanagerExtendSilenceRequest {
+            id: id.into(),
+            duration: "1h".into(),
+            dry_run: Some(dry_run),
+            confirm,
+        })
+    };
+    let v: Value = serde_json::from_str(&extract_text(
+        &extend("s-active", true, None).await.unwrap(),
+    ))
+    .unwrap();
+    let token = v["confirm"].as_str().unwrap().to_string();
+    let res = extend("s-active", false, Some(token)).await.unwrap();
+    let v: Value = serde_json::from_str(&extract_text(&res)).unwrap();
+    assert_eq!(v["previous_ends_at"], "2999-01-01T00:00:00Z");
+    assert_eq!(v["ends_at"], "2999-01-01T01:00:00.000Z");
+    let posted = am.posted.lock().unwrap().pop().unwrap();
+    assert_eq!(posted.id.as_deref(), Some("s-active"));
+
+    let res = extend("s-old", false, None).await.unwrap();
+    assert!(res.is_error);
+    assert!(extract_text(&res).contains("expired"));
+
+    let res = alertmanager_expire_silence(AlertmanagerExpireSilenceRequest {
+        id: "s-active".into(),
+        dry_run: None,
+        confirm: None,
+    })
+    .await
+    .unwrap();
+    let v: Value = serde_json::from_str(&extract_text(&res)).unwrap();
+    assert_eq!(v["unsilenced_alerts"]["count"], 1);
+    assert_eq!(v["unsilenced_alerts"]["alerts"][0]["fingerprint"], "d4e5f6");
+    assert!(am.expired.lock().unwrap().is_empty());
+    let token = v["confirm"].as_str().unwrap().to_string();
+
+    let res = alertmanager_expire_silence(AlertmanagerExpireSilenceRequest {
+        id: "s-active".into(),
+        dry_run: Some(false),
+        confirm: Some(token),
+    })
+    .await
+    .unwrap();
+    assert!(!res.is_error);
+    assert_eq!(*am.expired.lock().unwrap(), vec!["s-active"]);
+
+    // The scrape tool is only offered with a host allowlist and refuses other hosts
+    let tools = tools_list(None).await.unwrap().tools;
+    assert!(!tools.iter().any(|t| t.name == "prometheus_scrape_target"));
+    set_scrape_allowed_hosts(vec!["node-exporter:9100".into()]);
+    let res = prometheus_scrape_target(PrometheusScrapeTargetRequest {
+        url: "http://169.254.169.254/latest/meta-data".into(),
+        metric: None,
+        include_samples: None,
+    })
+    .await
+    .unwrap();
+    assert!(res.is_error);
+    assert!(extract_text(&res).contains("not on the scrape allowlist"));
+
+    // With an allowlist, the Prometheus tools accept a per-call tenant
+    set_allowed_tenants(vec!["team-a".into(), "team-b".into()]);
+    let tools = tools_list(None).await.unwrap().tools;
+    let query_tool = tools.iter().find(|t| t.name == "prometheus_query").unwrap();
+    let tenant = &query_tool.input_schema.properties["tenant"];
+    assert!(tenant
+        .description
+        .as_ref()
+        .unwrap()
+        .contains("team-a, team-b"));
+    let scrape_tool = tools
+        .iter()
+        .find(|t| t.name == "prometheus_scrape_target")
+        .unwrap();
+    assert!(!scrape_tool.input_schema.properties.contains_key("tenant"));
+    assert!(tools
+        .iter()
+        .filter(|t| t.name.starts_with("alertmanager_"))
+        .all(|t| !t.input_schema.properties.contains_key("tenant")));
+
+    // Thanos query parameters are rejected unless the backend is Thanos
+    let thanos_query = || PrometheusQueryRequest {
+        query: "up".into(),
+        time: None,
+        stats: None,
+        timeout: None,
+        limit: None,
+        dedup: Some(false),
+        partial_response: Some(true),
+        max_source_resolution: Some("5m".into()),
+        engine: None,
+    };
+    let res = prometheus_query(thanos_query()).await.unwrap();
+    assert!(res.is_error);
+    assert!(extract_text(&res).contains("dedup, partial_response, max_source_resolution"));
+    let tools = tools_list(None).await.unwrap().tools;
+    let query_tool = tools.iter().find(|t| t.name == "prometheus_query").unwrap();
+    assert!(!query_tool.input_schema.properties.contains_key("dedup"));
+
+    set_backend_flavor(BackendFlavor::Thanos);
+    let tools = tools_list(None).await.unwrap().tools;
+    for name in ["prometheus_query", "prometheus_query_range"] {
+        let tool = tools.iter().find(|t| t.name == name).unwrap();
+        for arg in [
+            "dedup",
+            "partial_response",
+            "max_source_resolution",
+            "engine",
+        ] {
+            assert!(
+                tool.input_schema.properties.contains_key(arg),
+                "{name} {arg}"
+            );
+        }
+    }
+    let res = prometheus_query(thanos_query()).await.unwrap();
+    assert!(!res.is_error);
+    match &res.content[1] {
+        CallToolResultContent::Text { text } => {
+            assert!(text.starts_with("Warnings:"));
+            assert!(text.contains("- receive-0: rpc error"));
+        }
+        _ => panic!("expected partial response warnings"),
+    }
+    let res = prometheus_query(PrometheusQueryRequest {
+        engine: Some("mimir".into()),
+        ..thanos_query()
+    })
+    .await
+    .unwrap();
+    assert!(res.is_error);
+    set_backend_flavor(BackendFlavor::Prometheus);
+
+    set_admin_tools_enabled(true);
+    let tools = tools_list(None).await.unwrap().tools;
+    let delete_tool = tools
+        .iter()
+        .find(|t| t.name == "prometheus_admin_delete_series")
+        .expect("delete_series listed when enabled");
+    let annotations = serde_json::to_value(delete_tool.annotations.as_ref().unwrap()).unwrap();
+    assert_eq!(annotations["destructiveHint"], true);
+    assert_eq!(annotations["readOnlyHint"], false);
+    assert!(tools
+        .iter()
+        .filter(|t| t.name.starts_with("prometheus_admin_"))
+        .all(|t| t.annotations.as_ref().unwrap().destructive_hint == Some(true)));
+
+    // Dry run is the default and reports the affected series
+    let res = prometheus_admin_delete_series(PrometheusAdminDeleteSeriesRequest {
+        match_strings: vec!["up{job=\"bad\"}".into()],
+        start: None,
+        end: None,
+        dry_run: None,
+    })
+    .await
+    .unwrap();
+    assert!(!res.is_error);
+    let v: Value = serde_json::from_str(&extract_text(&res)).unwrap();
+    assert_eq!(v["dry_run"], true);
+    assert_eq!(v["series_listed"], 3);
+    assert_eq!(v["truncated"], false);
+    assert_eq!(v["series"][0]["__name__"], "up");
+
+    let res = prometheus_admin_delete_series(PrometheusAdminDeleteSeriesRequest {
+        match_strings: vec!["up{job=\"bad\"}".into()],
+        start: None,
+        end: None,
+        dry_run: Some(false),
+    })
+    .await
+    .unwrap();
+    let v: Value = serde_json::from_str(&extract_text(&res)).unwrap();
+    assert_eq!(v["dry_run"], false);
+
+    let res = prometheus_admin_snapshot(PrometheusAdminSnapshotRequest {
+        skip_head: None,
+        dry_run: Some(false),
+    })
+    .await
+    .unwrap();
+    let v: Value = serde_json::from_str(&extract_text(&res)).unwrap();
+    assert_eq!(v["name"], "20250927T120000Z-6e6f6e6f");
+
+    let res = prometheus_read_raw(PrometheusReadRawRequest {
+        selector: "up".into(),
+        start: "1700000000.5".into(),
+        end: "2023-11-14T22:13:30Z".into(),
+    })
+    .await
+    .unwrap();
+    assert!(!res.is_error);
+    let v: Value = serde_json::from_str(&extract_text(&res)).unwrap();
+    assert_eq!(v[0]["samples"][0], json!([1700000000500i64, "1"]));
+    assert_eq!(v[0]["samples"][1], json!([1700000010000i64, "NaN"]));
+
+    let res = prometheus_read_raw(PrometheusReadRawRequest {
+        selector: "up".into(),
+        start: "yesterday".into(),
+        end: "now".into(),
+    })
+    .await
+    .unwrap();
+    assert!(res.is_error);
+
+    let res = prometheus_federate(PrometheusFederateRequest {
+        match_strings: vec!["{job=\"edge\"}".into()],
+    })
+    .await
+    .unwrap();
+    assert!(!res.is_error);
+    let v: Value = serde_json::from_str(&extract_text(&res)).unwrap();
+    assert_eq!(v[0]["type"], "gauge");
+    assert_eq!(v[0]["samples"][0]["labels"]["instance"], "edge-1:9090");
+    assert_eq!(v[0]["samples"][0]["value"], "1");
+    assert_eq!(v[0]["samples"][0]["timestamp_ms"], 1700000000000i64);
+
+    let res = prometheus_get_rules(PrometheusGetRulesRequest {
+        rule_type: Some("alert".into()),
+        rule_name: Some(vec!["SlowRequests".into()]),
+        rule_group: None,
+        file: None,
+    })
+    .await
+    .unwrap();
+    let text = extract_text(&res);
+    assert!(text.contains("SlowRequests"));
+    assert!(text.contains("\"type\": \"alerting\""));
+    assert!(text.contains("> 0.5"));
+
+    let res = prometheus_get_status(PrometheusGetStatusRequest {
+        section: "buildinfo".into(),
+    })
+    .await
+    .unwrap();
+    assert!(extract_text(&res).contains("3.5.0"));
+
+    let res = prometheus_get_status(PrometheusGetStatusRequest {
+        section: "config".into(),
+    })
+    .await
+    .unwrap();
+    let text = extract_text(&res);
+    assert!(text.contains("scrape_interval: 15s"));
+    assert!(text.contains("password: <redacted>"));
+    assert!(!text.contains("hunter2"));
+
+    let res = prometheus_get_status(PrometheusGetStatusRequest {
+        section: "tsdb".into(),
+    })
+    .await
+    .unwrap();
+    assert!(res.is_error);
+
     // Verify tools/list includes `items` for array properties (fix for validator requiring items)
     let tools_res = tools_list(None).await.unwrap();
     let series_tool = tools_res